@@ -12,27 +12,48 @@
 //! - writers that behave like `stdout` and `stderr` but write into the capture
 //! - macros ([`print!`], [`println!`], [`eprint!`], [`eprintln!`], [`dbg!`]) that mirror the
 //!   standard ones but route into Kitest's capture
+//! - with the `log` feature, a logger capturing records of the `log` crate in the `log` submodule
+//! - with the `tracing` feature, a subscriber layer capturing events of the `tracing` crate and
+//!   per test spans in the `tracing` submodule
+//! - a [`CaptureHandle`] to route output of other threads into the capture of a test, see also
+//!   [`kitest::thread`](crate::thread)
 //!
 //! This is only a best effort approach.
 //! Output written to the real stdout or stderr will still go to the terminal.
 //! In practice, tests should not rely on captured output unless they opt into these capture aware
 //! macros or otherwise write through the capture API.
+//!
+//! On Linux, the [`fd`] module additionally allows redirecting the process stdout and stderr
+//! file descriptors, which also captures output of `std::println!`, C libraries and subprocesses.
 
 use std::{
     any::Any,
-    backtrace::Backtrace,
+    backtrace::{Backtrace, BacktraceStatus},
+    borrow::Cow,
     cell::RefCell,
+    env,
     fmt::{self, Debug, Display},
     io::{self, Write},
     mem,
-    panic::{self, PanicHookInfo},
+    ops::Range,
+    panic::{self, Location, PanicHookInfo},
     sync::{
-        LazyLock,
+        Arc, LazyLock, Mutex, MutexGuard, PoisonError,
         atomic::{AtomicBool, Ordering},
     },
     thread::{self, ThreadId},
+    time::{Duration, Instant},
 };
 
+#[cfg(target_os = "linux")]
+pub mod fd;
+
+#[cfg(feature = "log")]
+pub mod log;
+
+#[cfg(feature = "tracing")]
+pub mod tracing;
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum OutputTarget {
     Stdout,
@@ -57,16 +78,77 @@ impl From<StderrTarget> for OutputTarget {
     }
 }
 
+/// A single write into an [`OutputCapture`].
 #[derive(Debug)]
 pub struct OutputEvent {
     pub target: OutputTarget,
-    range: std::ops::Range<usize>,
+    /// Time since the start of the test when the write happened.
+    pub elapsed: Duration,
+    range: Range<usize>,
 }
 
-#[derive(Debug, Default)]
+/// A piece of captured output, see [`OutputCapture::chunks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputChunk<'c> {
+    /// Output of a single write, possibly cut at the start if it was partially omitted.
+    Output {
+        target: OutputTarget,
+        /// Time since the start of the test when the write happened.
+        elapsed: Duration,
+        bytes: &'c [u8],
+    },
+
+    /// Output that was dropped to stay within the limit, see [`OutputCapture::set_limit`].
+    Omitted { len: usize },
+}
+
+/// Bookkeeping of a capture that exceeded its limit.
+///
+/// The buffer is split into a head, which is never touched again, and a tail.
+/// The tail is allowed to grow up to twice its budget before the oldest bytes are dropped, only
+/// the newest bytes within the budget are visible.
+#[derive(Debug, Clone)]
+struct Truncation {
+    head_events: usize,
+    head_len: usize,
+    tail_budget: usize,
+    dropped: usize,
+    marker: Vec<u8>,
+}
+
+impl Truncation {
+    fn visible_start(&self, buf_len: usize) -> usize {
+        buf_len.saturating_sub(self.tail_budget).max(self.head_len)
+    }
+
+    fn omitted(&self, buf_len: usize) -> usize {
+        self.dropped + self.visible_start(buf_len) - self.head_len
+    }
+}
+
+#[derive(Debug)]
 pub struct OutputCapture {
     buf: Vec<u8>,
     events: Vec<OutputEvent>,
+    started: Instant,
+    limit: Option<usize>,
+    truncation: Option<Truncation>,
+    live: Option<LiveOutput>,
+    shared: Option<Arc<Mutex<OutputCapture>>>,
+}
+
+impl Default for OutputCapture {
+    fn default() -> Self {
+        Self {
+            buf: Vec::new(),
+            events: Vec::new(),
+            started: Instant::now(),
+            limit: None,
+            truncation: None,
+            live: None,
+            shared: None,
+        }
+    }
 }
 
 impl OutputCapture {
@@ -75,22 +157,240 @@ impl OutputCapture {
     }
 
     pub fn clear(&mut self) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.clear();
+        }
+
         self.buf.clear();
-        self.events.clear()
+        self.events.clear();
+        self.truncation = None;
+        self.started = Instant::now();
     }
 
     pub fn take(&mut self) -> Self {
-        let buf = mem::take(&mut self.buf);
-        let events = mem::take(&mut self.events);
-        Self { buf, events }
+        if let Some(shared) = self.shared.take() {
+            let mut shared = shared.lock().unwrap_or_else(PoisonError::into_inner);
+            self.live = shared.live.take();
+            self.limit = shared.limit;
+            self.started = Instant::now();
+            return shared.take();
+        }
+
+        let started = mem::replace(&mut self.started, Instant::now());
+        Self {
+            buf: mem::take(&mut self.buf),
+            events: mem::take(&mut self.events),
+            started,
+            limit: self.limit,
+            truncation: self.truncation.take(),
+            live: None,
+            shared: None,
+        }
+    }
+
+    fn lock_shared(&self) -> Option<MutexGuard<'_, OutputCapture>> {
+        self.shared
+            .as_ref()
+            .map(|shared| shared.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Move the content into a capture that other threads can write into.
+    fn share(&mut self) -> Arc<Mutex<OutputCapture>> {
+        let shared = self.shared.get_or_insert_with(|| {
+            Arc::new(Mutex::new(OutputCapture {
+                buf: mem::take(&mut self.buf),
+                events: mem::take(&mut self.events),
+                started: self.started,
+                limit: self.limit,
+                truncation: self.truncation.take(),
+                live: self.live.take(),
+                shared: None,
+            }))
+        });
+        Arc::clone(shared)
+    }
+
+    /// Limit the amount of stored output to `limit` bytes.
+    ///
+    /// Once more output is written, the first half of the limit is kept as is and only the most
+    /// recent output is kept for the second half.
+    /// The output in between is replaced by a marker stating how many bytes were omitted.
+    /// This keeps the start of the output, which usually explains the setup, and the end, which
+    /// usually explains the failure.
+    ///
+    /// Pass `None` to remove the limit, output that was already omitted stays omitted.
+    pub fn set_limit(&mut self, limit: Option<usize>) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.set_limit(limit);
+        }
+
+        self.limit = limit;
+    }
+
+    /// Prepare the capture for the next test.
+    ///
+    /// Timestamps of events are relative to the last call of this.
+    pub(crate) fn start_test(&mut self, limit: Option<usize>) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.start_test(limit);
+        }
+
+        self.limit = limit;
+        self.started = Instant::now();
+    }
+
+    /// Forward output to the process stdout and stderr instead of storing it.
+    ///
+    /// Output is forwarded line by line as soon as a line is complete, each line is written in a
+    /// single call so lines of different threads do not interleave.
+    /// If `prefix` is given, every line is prefixed with `[prefix] `.
+    pub fn start_live(&mut self, prefix: Option<String>) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.start_live(prefix);
+        }
+
+        self.stop_live();
+        self.live = Some(LiveOutput {
+            prefix,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    }
+
+    /// Stop forwarding output, see [`start_live`](Self::start_live).
+    ///
+    /// Unfinished lines are terminated and forwarded.
+    pub fn stop_live(&mut self) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.stop_live();
+        }
+
+        if let Some(mut live) = self.live.take() {
+            for (target, line) in live.finish() {
+                LiveOutput::emit(target, &line);
+            }
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        if let Some(shared) = self.lock_shared() {
+            return shared.is_live();
+        }
+
+        self.live.is_some()
+    }
+
+    /// Append the output of `other`, keeping its timestamps.
+    ///
+    /// Omitted output of `other` is appended as its marker on stderr.
+    pub fn append(&mut self, other: &OutputCapture) {
+        for chunk in other.chunks() {
+            let bytes = other.chunk_bytes(chunk);
+            match chunk {
+                OutputChunk::Output {
+                    target, elapsed, ..
+                } => self.push_event_at(bytes, target, Some(elapsed)),
+                OutputChunk::Omitted { .. } => {
+                    self.push_event_at(bytes, OutputTarget::Stderr, None)
+                }
+            }
+        }
     }
 
     fn push_event(&mut self, buf: &[u8], target: OutputTarget) {
+        self.push_event_at(buf, target, None)
+    }
+
+    fn push_event_at(&mut self, buf: &[u8], target: OutputTarget, elapsed: Option<Duration>) {
+        if let Some(mut shared) = self.lock_shared() {
+            return shared.push_event_at(buf, target, elapsed);
+        }
+
+        if let Some(live) = &mut self.live {
+            for line in live.lines(buf, target) {
+                LiveOutput::emit(target, &line);
+            }
+            return;
+        }
+
         let start = self.buf.len();
         let end = start + buf.len();
         let range = start..end;
+        let elapsed = elapsed.unwrap_or_else(|| self.started.elapsed());
         self.buf.extend_from_slice(buf);
-        self.events.push(OutputEvent { target, range });
+        self.events.push(OutputEvent {
+            target,
+            elapsed,
+            range,
+        });
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+
+        let mut truncation = match self.truncation.take() {
+            Some(truncation) => truncation,
+            None if self.buf.len() <= limit => return,
+            None => {
+                let head_len = limit / 2;
+                Truncation {
+                    head_events: self.split_event_at(head_len),
+                    head_len,
+                    tail_budget: limit - head_len,
+                    dropped: 0,
+                    marker: Vec::new(),
+                }
+            }
+        };
+
+        // drop bytes only once the tail is twice its budget, that keeps appending amortized O(1)
+        let visible_start = truncation.visible_start(self.buf.len());
+        let cut = visible_start - truncation.head_len;
+        if cut > truncation.tail_budget || truncation.tail_budget == 0 {
+            self.buf.drain(truncation.head_len..visible_start);
+            let tail = &self.events[truncation.head_events..];
+            let removed = tail.partition_point(|event| event.range.end <= visible_start);
+            self.events
+                .drain(truncation.head_events..truncation.head_events + removed);
+            for event in &mut self.events[truncation.head_events..] {
+                event.range = event.range.start.max(visible_start) - cut..event.range.end - cut;
+            }
+            truncation.dropped += cut;
+        }
+
+        let separator = match self.buf[..truncation.head_len].last() {
+            None | Some(b'\n') => "",
+            Some(_) => "\n",
+        };
+        truncation.marker = format!(
+            "{separator}[... {} bytes omitted ...]\n",
+            truncation.omitted(self.buf.len())
+        )
+        .into_bytes();
+        self.truncation = Some(truncation);
+    }
+
+    /// Split the event containing byte `at` into two and return the index of the second one.
+    fn split_event_at(&mut self, at: usize) -> usize {
+        let idx = self.events.partition_point(|event| event.range.end <= at);
+        let Some(event) = self.events.get_mut(idx) else {
+            return idx;
+        };
+        if event.range.start >= at {
+            return idx;
+        }
+
+        let second = OutputEvent {
+            target: event.target,
+            elapsed: event.elapsed,
+            range: at..event.range.end,
+        };
+        event.range.end = at;
+        self.events.insert(idx + 1, second);
+        idx + 1
     }
 
     pub fn stdout(&mut self) -> OutputWrite<'_, StdoutTarget> {
@@ -107,45 +407,259 @@ impl OutputCapture {
         }
     }
 
+    /// Return the number of bytes omitted because of the limit, see [`set_limit`](Self::set_limit).
+    pub fn omitted(&self) -> usize {
+        self.truncation
+            .as_ref()
+            .map_or(0, |truncation| truncation.omitted(self.buf.len()))
+    }
+
+    /// Return the stored output in the order it was written.
+    ///
+    /// Without a [limit](Self::set_limit), this is all output.
+    /// Once output was omitted, these are the retained bytes without the omission marker, which
+    /// may include some bytes that are about to be dropped.
+    /// Use [`raw_with_marker`](Self::raw_with_marker) for the truncated view.
     pub fn raw(&self) -> &[u8] {
         &self.buf
     }
 
-    fn read_target(&self, target: OutputTarget) -> impl Iterator<Item = &[u8]> {
-        self.events
+    /// Return the stored output in the order it was written, as shown to users.
+    ///
+    /// If output was omitted, the omission marker is included in place of the omitted bytes.
+    pub fn raw_with_marker(&self) -> Cow<'_, [u8]> {
+        match self.truncation {
+            None => Cow::Borrowed(&self.buf),
+            Some(_) => Cow::Owned(
+                self.chunks()
+                    .flat_map(|chunk| self.chunk_bytes(chunk))
+                    .copied()
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Iterate over the stored output in the order it was written, including timestamps.
+    pub fn chunks(&self) -> impl Iterator<Item = OutputChunk<'_>> {
+        let (head_events, visible_start) = match &self.truncation {
+            None => (self.events.len(), 0),
+            Some(truncation) => (
+                truncation.head_events,
+                truncation.visible_start(self.buf.len()),
+            ),
+        };
+        let (head, tail) = self.events.split_at(head_events);
+
+        let chunk = move |event: &OutputEvent, start: usize| OutputChunk::Output {
+            target: event.target,
+            elapsed: event.elapsed,
+            bytes: &self.buf[event.range.start.max(start)..event.range.end],
+        };
+        let omitted = self
+            .truncation
+            .as_ref()
+            .map(|truncation| OutputChunk::Omitted {
+                len: truncation.omitted(self.buf.len()),
+            });
+        let tail = tail
             .iter()
-            .filter(move |event| event.target == target)
-            .map(|event| &event.range)
-            .cloned()
-            .map(|range| &self.buf[range])
+            .filter(move |event| event.range.end > visible_start)
+            .map(move |event| chunk(event, visible_start));
+        head.iter()
+            .map(move |event| chunk(event, 0))
+            .chain(omitted)
+            .chain(tail)
+    }
+
+    fn chunk_bytes<'c>(&'c self, chunk: OutputChunk<'c>) -> &'c [u8] {
+        match (chunk, &self.truncation) {
+            (OutputChunk::Output { bytes, .. }, _) => bytes,
+            (OutputChunk::Omitted { .. }, Some(truncation)) => &truncation.marker,
+            (OutputChunk::Omitted { .. }, None) => &[],
+        }
+    }
+
+    fn read_target(&self, target: OutputTarget) -> impl Iterator<Item = &[u8]> {
+        self.chunks()
+            .filter(move |chunk| match chunk {
+                OutputChunk::Output { target: t, .. } => *t == target,
+                OutputChunk::Omitted { .. } => true,
+            })
+            .map(|chunk| self.chunk_bytes(chunk))
     }
 
+    /// Iterate over the stdout output.
+    ///
+    /// If output was omitted, the omission marker is included.
     pub fn read_stdout(&self) -> impl Iterator<Item = &[u8]> {
         self.read_target(OutputTarget::Stdout)
     }
 
+    /// Iterate over the stderr output.
+    ///
+    /// If output was omitted, the omission marker is included.
     pub fn read_stderr(&self) -> impl Iterator<Item = &[u8]> {
         self.read_target(OutputTarget::Stderr)
     }
+
+    fn lines(&self, target: OutputTarget) -> impl Iterator<Item = String> + use<> {
+        let bytes = self.read_target(target).collect::<Vec<_>>().concat();
+        let lines: Vec<String> = bytes
+            .split_inclusive(|byte| *byte == b'\n')
+            .map(|line| {
+                let line = line.strip_suffix(b"\n").unwrap_or(line);
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                String::from_utf8_lossy(line).into_owned()
+            })
+            .collect();
+        lines.into_iter()
+    }
+
+    /// Iterate over the lines of the stdout output.
+    ///
+    /// Lines written in several pieces are joined, line endings are removed and invalid UTF-8 is
+    /// replaced like in [`String::from_utf8_lossy`].
+    pub fn stdout_lines(&self) -> impl Iterator<Item = String> + use<> {
+        self.lines(OutputTarget::Stdout)
+    }
+
+    /// Iterate over the lines of the stderr output.
+    ///
+    /// See [`stdout_lines`](Self::stdout_lines).
+    pub fn stderr_lines(&self) -> impl Iterator<Item = String> + use<> {
+        self.lines(OutputTarget::Stderr)
+    }
 }
 
 // implement Clone manually to avoid clonable events, they don't make sense in absence of the capture
 impl Clone for OutputCapture {
     fn clone(&self) -> Self {
         Self {
+            live: None,
+            shared: None,
             buf: self.buf.clone(),
             events: self
                 .events
                 .iter()
                 .map(|event| OutputEvent {
                     target: event.target,
+                    elapsed: event.elapsed,
                     range: event.range.clone(),
                 })
                 .collect(),
+            started: self.started,
+            limit: self.limit,
+            truncation: self.truncation.clone(),
         }
     }
 }
 
+/// A handle to the output capture of a thread, used to capture output of other threads.
+///
+/// [`TEST_OUTPUT_CAPTURE`] is thread local, so output of threads spawned by a test does not end up
+/// in the output of that test.
+/// Obtain a handle on the test thread with [`CaptureHandle::current`] and
+/// [`install`](CaptureHandle::install) it on other threads to route their output, including
+/// panic messages, into the same capture.
+/// Output of all threads sharing a capture is stored in the order it was written.
+///
+/// The functions in [`kitest::thread`](crate::thread) do this automatically.
+///
+/// While a capture is shared, its output is only accessible via [`OutputCapture::take`], which
+/// also ends the sharing.
+/// Output written by other threads after that is discarded.
+#[derive(Debug, Clone)]
+pub struct CaptureHandle(Arc<Mutex<OutputCapture>>);
+
+impl CaptureHandle {
+    /// Return a handle to the output capture of the current thread.
+    pub fn current() -> Self {
+        Self(TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::share))
+    }
+
+    /// Route all output of the current thread into the capture of this handle.
+    pub fn install(&self) {
+        TEST_OUTPUT_CAPTURE.with_borrow_mut(|capture| capture.shared = Some(Arc::clone(&self.0)));
+    }
+}
+
+#[derive(Debug)]
+struct LiveOutput {
+    prefix: Option<String>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+impl LiveOutput {
+    fn pending(&mut self, target: OutputTarget) -> &mut Vec<u8> {
+        match target {
+            OutputTarget::Stdout => &mut self.stdout,
+            OutputTarget::Stderr => &mut self.stderr,
+        }
+    }
+
+    fn line(&self, content: &[u8]) -> Vec<u8> {
+        let mut line = Vec::with_capacity(content.len() + 1);
+        if let Some(prefix) = &self.prefix {
+            line.extend_from_slice(format!("[{prefix}] ").as_bytes());
+        }
+        line.extend_from_slice(content);
+        if !content.ends_with(b"\n") {
+            line.push(b'\n');
+        }
+        line
+    }
+
+    /// Buffer `buf` and return all lines completed by it.
+    fn lines(&mut self, buf: &[u8], target: OutputTarget) -> Vec<Vec<u8>> {
+        let pending = self.pending(target);
+        pending.extend_from_slice(buf);
+        let Some(end) = pending.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = pending.drain(..=end).collect();
+        complete
+            .split_inclusive(|byte| *byte == b'\n')
+            .map(|content| self.line(content))
+            .collect()
+    }
+
+    /// Return the unfinished lines.
+    fn finish(&mut self) -> Vec<(OutputTarget, Vec<u8>)> {
+        [OutputTarget::Stdout, OutputTarget::Stderr]
+            .into_iter()
+            .filter_map(|target| {
+                let pending = mem::take(self.pending(target));
+                (!pending.is_empty()).then(|| (target, self.line(&pending)))
+            })
+            .collect()
+    }
+
+    fn emit(target: OutputTarget, line: &[u8]) {
+        // `write_all` locks the stream once, keeping the line in one piece
+        let _ = match target {
+            OutputTarget::Stdout => io::stdout().write_all(line),
+            OutputTarget::Stderr => io::stderr().write_all(line),
+        };
+    }
+}
+
+/// Forwards the output of the current thread while alive, see [`OutputCapture::start_live`].
+pub(crate) struct LiveOutputGuard(());
+
+impl LiveOutputGuard {
+    pub(crate) fn start(prefix: Option<&str>) -> Self {
+        TEST_OUTPUT_CAPTURE.with_borrow_mut(|capture| capture.start_live(prefix.map(String::from)));
+        Self(())
+    }
+}
+
+impl Drop for LiveOutputGuard {
+    fn drop(&mut self) {
+        TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::stop_live);
+    }
+}
+
 #[derive(Debug)]
 pub struct OutputWrite<'c, Target> {
     capture: &'c mut OutputCapture,
@@ -212,12 +726,210 @@ pub fn reset_first_panic() {
     FIRST_PANIC.store(true, Ordering::Relaxed);
 }
 
-static DISABLED_BACKTRACE: LazyLock<String> =
-    LazyLock::new(|| format!("{}", Backtrace::disabled()));
+/// A source location, as reported by panics and backtraces.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PanicLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl PanicLocation {
+    /// Parse a location formatted as `file:line:column`.
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        let file = parts.next()?.to_string();
+        Some(Self { file, line, column })
+    }
+}
+
+impl From<&Location<'_>> for PanicLocation {
+    fn from(location: &Location<'_>) -> Self {
+        Self {
+            file: location.file().to_string(),
+            line: location.line(),
+            column: location.column(),
+        }
+    }
+}
+
+impl Display for PanicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+/// A single frame of a captured backtrace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BacktraceFrame {
+    /// The demangled name of the function, or `<unknown>` if it could not be resolved.
+    pub function: String,
+    /// The source location, if debug info is available.
+    pub location: Option<PanicLocation>,
+}
+
+/// Return the frames of a captured backtrace.
+///
+/// Inlined functions are listed as frames of their own, like in the textual representation.
+/// Returns no frames if the backtrace was not captured.
+pub fn backtrace_frames(backtrace: &Backtrace) -> Vec<BacktraceFrame> {
+    match backtrace.status() {
+        BacktraceStatus::Captured => parse_backtrace_frames(&backtrace.to_string()),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the frames of a captured backtrace from its textual representation.
+///
+/// The standard library does not expose the frames of a [`Backtrace`] on stable Rust.
+fn parse_backtrace_frames(backtrace: &str) -> Vec<BacktraceFrame> {
+    // frames are printed as `{idx:>4}: {function}`, the functions inlined into a frame follow
+    // without the index, aligned with the function of the frame
+    const INLINED_INDENT: usize = 6;
+
+    let mut frames: Vec<BacktraceFrame> = Vec::new();
+    for line in backtrace.lines() {
+        let trimmed = line.trim();
+        if let Some(location) = trimmed.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.location = PanicLocation::parse(location);
+            }
+            continue;
+        }
+
+        let function = match trimmed.split_once(": ") {
+            Some((idx, function))
+                if !idx.is_empty() && idx.bytes().all(|byte| byte.is_ascii_digit()) =>
+            {
+                function
+            }
+            _ if !trimmed.is_empty()
+                && !frames.is_empty()
+                && line.len() - line.trim_start().len() == INLINED_INDENT =>
+            {
+                trimmed
+            }
+            _ => continue,
+        };
+        frames.push(BacktraceFrame {
+            function: function.to_string(),
+            location: None,
+        });
+    }
+    frames
+}
+
+/// How backtraces of panics are printed.
+///
+/// This mirrors the styles of the panic hook of the standard library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktraceStyle {
+    /// Only print the frames of the test, see [`short_backtrace`].
+    Short,
+    /// Print all frames.
+    Full,
+}
+
+impl BacktraceStyle {
+    /// Read the style from the `RUST_BACKTRACE` environment variable.
+    ///
+    /// `full` selects [`Full`](Self::Full), anything else [`Short`](Self::Short).
+    /// Whether a backtrace is captured at all is decided by [`Backtrace::capture`].
+    pub fn from_env() -> Self {
+        static STYLE: LazyLock<BacktraceStyle> =
+            LazyLock::new(|| match env::var_os("RUST_BACKTRACE") {
+                Some(value) if value == "full" => BacktraceStyle::Full,
+                _ => BacktraceStyle::Short,
+            });
+        *STYLE
+    }
+}
+
+/// Trim the frames outside of the test function.
+///
+/// Like the panic hook of the standard library, this drops the frames of the panic machinery, up
+/// to and including a frame named `__rust_end_short_backtrace`, and all frames starting at a
+/// frame named `__rust_begin_short_backtrace`.
+/// Kitest calls every test function through such a marker, so the remaining frames are the ones
+/// of the test itself.
+///
+/// Returns all frames if neither marker is found.
+pub fn short_backtrace(frames: &[BacktraceFrame]) -> &[BacktraceFrame] {
+    let start = frames
+        .iter()
+        .rposition(|frame| frame.function.contains("__rust_end_short_backtrace"))
+        .map_or(0, |idx| idx + 1);
+    let frames = &frames[start..];
+    let end = frames
+        .iter()
+        .position(|frame| frame.function.contains("__rust_begin_short_backtrace"))
+        .unwrap_or(frames.len());
+    &frames[..end]
+}
+
+/// Write backtrace frames in the format of the standard library.
+///
+/// Frames are numbered starting from 0, so trimmed backtraces look like complete ones.
+pub fn write_backtrace_frames(
+    target: &mut impl Write,
+    frames: &[BacktraceFrame],
+) -> io::Result<()> {
+    for (idx, frame) in frames.iter().enumerate() {
+        writeln!(target, "{idx:>4}: {}", frame.function)?;
+        if let Some(location) = &frame.location {
+            writeln!(target, "             at {location}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Structured information about a panic.
+///
+/// The default panic hook records this for every panic in [`TEST_PANIC_INFO`] of the panicking
+/// thread.
+/// The built in runners attach the recorded info of the test thread to the
+/// [`TestOutcome`](crate::outcome::TestOutcome) of tests that failed by panicking, get it via
+/// `outcome.attachments.get::<PanicInfo>()`.
+///
+/// Custom [`PanicHookProvider`]s can record it too, using [`PanicInfo::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicInfo {
+    /// Where the panic happened.
+    pub location: Option<PanicLocation>,
+    /// The name of the panicking thread, if it has one.
+    pub thread: Option<String>,
+    /// The panic payload formatted as text.
+    pub payload: String,
+    /// The frames of the backtrace, if one was captured.
+    ///
+    /// These are all frames, regardless of the [`BacktraceStyle`], see [`short_backtrace`].
+    pub backtrace: Option<Vec<BacktraceFrame>>,
+}
+
+impl PanicInfo {
+    /// Collect the information of a panic on the current thread.
+    ///
+    /// Backtrace frames are only included if `backtrace` was actually captured.
+    pub fn new(panic_hook_info: &PanicHookInfo<'_>, backtrace: Option<&Backtrace>) -> Self {
+        Self {
+            location: panic_hook_info.location().map(PanicLocation::from),
+            thread: thread::current().name().map(String::from),
+            payload: payload_as_str(panic_hook_info.payload()).to_string(),
+            backtrace: backtrace
+                .filter(|backtrace| backtrace.status() == BacktraceStatus::Captured)
+                .map(backtrace_frames),
+        }
+    }
+}
 
 fn default_panic_hook(panic_hook_info: &PanicHookInfo<'_>) {
     // for reference: https://github.com/rust-lang/rust/blob/dfe1b8c97bcde283102f706d5dcdc3649e5e12e3/library/std/src/panicking.rs#L240
 
+    let backtrace = Backtrace::capture();
+    let panic_info = PanicInfo::new(panic_hook_info, Some(&backtrace));
+
     TEST_OUTPUT_CAPTURE
         .with_borrow_mut(|capture| {
             let thread = thread::current();
@@ -235,17 +947,27 @@ fn default_panic_hook(panic_hook_info: &PanicHookInfo<'_>) {
             let payload = payload_as_str(panic_hook_info.payload());
             stderr.write_fmt(format_args!(":\n{payload}\n"))?;
 
-            let backtrace = Backtrace::capture();
-            let backtrace = format!("{backtrace}");
-            match backtrace.as_str() == DISABLED_BACKTRACE.as_str() {
-                false => stderr.write_all(backtrace.as_bytes()),
-                true if FIRST_PANIC.swap(false, Ordering::Relaxed) => stderr.write_all(
-                    b"note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n"
-                ),
-                true => Ok(())
+            match (&panic_info.backtrace, BacktraceStyle::from_env()) {
+                (Some(frames), BacktraceStyle::Short) => {
+                    stderr.write_all(b"stack backtrace:\n")?;
+                    write_backtrace_frames(&mut stderr, short_backtrace(frames))?;
+                    stderr.write_all(
+                        b"note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.\n"
+                    )
+                }
+                (Some(_), BacktraceStyle::Full) => stderr.write_fmt(format_args!("{backtrace}")),
+                (None, _) => match backtrace.status() {
+                    BacktraceStatus::Disabled if FIRST_PANIC.swap(false, Ordering::Relaxed) => stderr.write_all(
+                        b"note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n"
+                    ),
+                    BacktraceStatus::Disabled => Ok(()),
+                    _ => stderr.write_fmt(format_args!("{backtrace}")),
+                },
             }
         })
         .expect("infallible for Vec<u8>");
+
+    TEST_PANIC_INFO.set(Some(panic_info));
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -294,10 +1016,20 @@ impl Drop for CapturePanicHookGuard {
 ///
 /// Note: this only affects Kitest's capture aware macros.
 /// Output written directly via [`std::println!`] and friends is not captured on stable Rust.
+///
+/// To show output while tests run, prefer
+/// [`TestHarness::with_no_capture`](crate::TestHarness::with_no_capture), which keeps output of
+/// parallel tests apart.
 pub static CAPTURE_OUTPUT_MACROS: AtomicBool = AtomicBool::new(true);
 
 thread_local! {
     pub static TEST_OUTPUT_CAPTURE: RefCell<OutputCapture> = RefCell::new(OutputCapture::new());
+
+    /// The [`PanicInfo`] of the last panic on this thread, recorded by the default panic hook.
+    ///
+    /// Runners take it after each test and attach it to the outcome of tests that failed by
+    /// panicking.
+    pub static TEST_PANIC_INFO: RefCell<Option<PanicInfo>> = const { RefCell::new(None) };
 }
 
 #[macro_export]
@@ -428,6 +1160,7 @@ macro_rules! dbg {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::*;
     use std::sync::atomic::Ordering;
 
     #[test]
@@ -468,4 +1201,276 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn live_output_splits_and_prefixes_lines() {
+        let mut live = LiveOutput {
+            prefix: Some(String::from("my_test")),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        };
+
+        assert!(live.lines(b"hello", OutputTarget::Stdout).is_empty());
+        assert!(live.lines(b"oh ", OutputTarget::Stderr).is_empty());
+        assert_eq!(
+            live.lines(b" world\nfoo\nba", OutputTarget::Stdout),
+            [&b"[my_test] hello world\n"[..], b"[my_test] foo\n"]
+        );
+        assert_eq!(
+            live.finish(),
+            [
+                (OutputTarget::Stdout, b"[my_test] ba\n".to_vec()),
+                (OutputTarget::Stderr, b"[my_test] oh \n".to_vec())
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_keeps_head_and_tail() {
+        let mut capture = OutputCapture::new();
+        capture.set_limit(Some(10));
+        capture.stdout().write_all(b"0123456789").unwrap();
+        assert_eq!(capture.omitted(), 0);
+
+        for byte in b"abcdefghijklmnopqrstuvwxyz" {
+            capture.stderr().write_all(&[*byte]).unwrap();
+        }
+        assert_eq!(capture.omitted(), 26);
+        assert!(capture.buf.len() <= 20);
+        assert_eq!(
+            &*capture.raw_with_marker(),
+            b"01234\n[... 26 bytes omitted ...]\nvwxyz"
+        );
+        assert!(capture.raw().starts_with(b"01234") && capture.raw().ends_with(b"vwxyz"));
+        assert!(!capture.raw().windows(7).any(|window| window == b"omitted"));
+        assert_eq!(
+            capture.read_stdout().collect::<Vec<_>>().concat(),
+            b"01234\n[... 26 bytes omitted ...]\n"
+        );
+        assert_eq!(
+            capture.read_stderr().collect::<Vec<_>>().concat(),
+            b"\n[... 26 bytes omitted ...]\nvwxyz"
+        );
+        assert_eq!(
+            capture.chunks().nth(1),
+            Some(OutputChunk::Omitted { len: 26 })
+        );
+
+        let mut copy = OutputCapture::new();
+        copy.append(&capture);
+        assert_eq!(copy.raw_with_marker(), capture.raw_with_marker());
+    }
+
+    #[test]
+    fn lines_join_writes_and_replace_invalid_utf8() {
+        let mut capture = OutputCapture::new();
+        capture.stdout().write_all(b"a").unwrap();
+        capture.stderr().write_all(b"err\n").unwrap();
+        capture.stdout().write_all(b"b\nc\r\n\xff\n").unwrap();
+        capture.stdout().write_all(b"d").unwrap();
+
+        assert_eq!(
+            capture.stdout_lines().collect::<Vec<_>>(),
+            ["ab", "c", "\u{FFFD}", "d"]
+        );
+        assert_eq!(capture.stderr_lines().collect::<Vec<_>>(), ["err"]);
+    }
+
+    #[test]
+    fn events_are_timestamped_relative_to_test_start() {
+        use crate::test_support::*;
+        use std::time::Duration;
+
+        fn write_stdout(s: &str) {
+            TEST_OUTPUT_CAPTURE
+                .with_borrow_mut(|capture| capture.stdout().write_all(s.as_bytes()))
+                .unwrap();
+        }
+
+        let tests = &[test! {
+            func: || {
+                write_stdout("first");
+                thread::sleep(Duration::from_millis(20));
+                write_stdout("second");
+                write_stdout("third");
+            }
+        }];
+
+        // let the start of the thread local capture lie in the past
+        TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::clear);
+        thread::sleep(Duration::from_millis(20));
+
+        let report = harness(tests).with_output_limit(Some(10)).run();
+        let output = &report.outcomes[0].1.output;
+        let elapsed: Vec<_> = output
+            .chunks()
+            .filter_map(|chunk| match chunk {
+                OutputChunk::Output { elapsed, .. } => Some(elapsed),
+                OutputChunk::Omitted { .. } => None,
+            })
+            .collect();
+
+        assert!(elapsed[0] < Duration::from_millis(20));
+        assert!(elapsed[1] >= Duration::from_millis(20));
+        assert!(elapsed.is_sorted());
+        assert_eq!(output.omitted(), 6);
+    }
+
+    #[test]
+    fn backtrace_frames_are_parsed() {
+        let backtrace = "   0: std::backtrace::Backtrace::capture
+             at /rustc/library/std/src/backtrace.rs:296:13
+   1: kitest::capture::tests::foo
+             at ./src/capture.rs:10:5
+   2: <unknown>
+      kitest::capture::tests::inlined
+             at ./src/capture.rs:20:9
+note: not a frame
+   3: main
+";
+        assert_eq!(
+            parse_backtrace_frames(backtrace),
+            [
+                BacktraceFrame {
+                    function: String::from("std::backtrace::Backtrace::capture"),
+                    location: Some(PanicLocation {
+                        file: String::from("/rustc/library/std/src/backtrace.rs"),
+                        line: 296,
+                        column: 13
+                    })
+                },
+                BacktraceFrame {
+                    function: String::from("kitest::capture::tests::foo"),
+                    location: Some(PanicLocation {
+                        file: String::from("./src/capture.rs"),
+                        line: 10,
+                        column: 5
+                    })
+                },
+                BacktraceFrame {
+                    function: String::from("<unknown>"),
+                    location: None
+                },
+                BacktraceFrame {
+                    function: String::from("kitest::capture::tests::inlined"),
+                    location: Some(PanicLocation {
+                        file: String::from("./src/capture.rs"),
+                        line: 20,
+                        column: 9
+                    })
+                },
+                BacktraceFrame {
+                    function: String::from("main"),
+                    location: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn short_backtrace_trims_outside_frames() {
+        let frame = |function: &str| BacktraceFrame {
+            function: String::from(function),
+            location: None,
+        };
+        let frames = [
+            frame("kitest::capture::default_panic_hook"),
+            frame("std::sys::backtrace::__rust_end_short_backtrace"),
+            frame("core::panicking::panic_fmt"),
+            frame("my_crate::tests::my_test"),
+            frame("kitest::test::__rust_begin_short_backtrace"),
+            frame("std::panicking::catch_unwind"),
+        ];
+        assert_eq!(short_backtrace(&frames), &frames[2..4]);
+        assert_eq!(short_backtrace(&frames[..1]), &frames[..1]);
+
+        let mut out = Vec::new();
+        write_backtrace_frames(&mut out, short_backtrace(&frames)).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "   0: core::panicking::panic_fmt\n   1: my_crate::tests::my_test\n"
+        );
+    }
+
+    #[test]
+    fn tests_are_called_through_short_backtrace_marker() {
+        use crate::test_support::*;
+
+        let tests = &[test! {
+            func: || {
+                let frames = backtrace_frames(&Backtrace::force_capture());
+                let short = short_backtrace(&frames);
+                assert!(short.len() < frames.len());
+                assert!(short.iter().all(|frame| !frame.function.contains("catch_unwind")));
+                assert!(frames.iter().any(|frame| frame.function.contains("catch_unwind")));
+            }
+        }];
+
+        let report = harness(tests)
+            .with_panic_handler(crate::panic::DefaultPanicHandler)
+            .run();
+        assert!(report.outcomes[0].1.passed());
+    }
+
+    #[test]
+    fn short_backtrace_ends_at_test_function() {
+        use crate::{
+            test::{TestFnHandle, TestResult},
+            test_support::*,
+        };
+
+        fn assert_called_directly(test_fn: &str) {
+            let frames = backtrace_frames(&Backtrace::force_capture());
+            let short = short_backtrace(&frames);
+            assert!(short.len() < frames.len());
+            let idx = short
+                .iter()
+                .rposition(|frame| frame.function.contains(test_fn))
+                .expect("test function is part of the short backtrace");
+            assert!(
+                short[idx + 1..]
+                    .iter()
+                    .all(|frame| !frame.function.contains("kitest::")),
+                "{short:#?}"
+            );
+        }
+
+        fn ptr_test() -> TestResult {
+            assert_called_directly("ptr_test");
+            ().into()
+        }
+
+        fn owned_test() {
+            assert_called_directly("owned_test");
+        }
+
+        async fn async_test() {
+            assert_called_directly("async_test");
+        }
+
+        let tests = &[
+            test! {func: TestFnHandle::from_const_fn(ptr_test)},
+            test! {func: TestFnHandle::from_boxed(owned_test)},
+            test! {func: TestFnHandle::from_future_fn(async_test)},
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(crate::panic::DefaultPanicHandler)
+            .run();
+        for (name, outcome) in report.outcomes {
+            assert!(outcome.passed(), "{name}: {:?}", outcome.status);
+        }
+    }
+
+    #[test]
+    fn no_capture_is_live_during_tests() {
+        // the forwarded output itself is checked in the `fd_capture` tests
+        let tests = &[test! {
+            func: || assert!(TEST_OUTPUT_CAPTURE.with_borrow(OutputCapture::is_live))
+        }];
+
+        let report = harness(tests).with_no_capture(true).run();
+        assert!(report.outcomes[0].1.passed());
+        assert!(!TEST_OUTPUT_CAPTURE.with_borrow(OutputCapture::is_live));
+    }
 }
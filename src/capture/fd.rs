@@ -0,0 +1,187 @@
+//! Capture of the real process stdout and stderr on Linux.
+//!
+//! The macros of the parent module only capture output that is written through them.
+//! Output from [`std::println!`], C libraries or subprocesses goes straight to the file
+//! descriptors 1 and 2 of the process.
+//!
+//! This module redirects these file descriptors into pipes while a closure runs, see [`capture`].
+//! Background threads drain the pipes, so a test writing a lot of output does not block.
+//! Once the closure returns, the original file descriptors are restored and the drained output
+//! is returned as an [`OutputCapture`].
+//!
+//! File descriptors are shared by the whole process.
+//! To keep the output attributable, only one redirection is active at any time; concurrent calls
+//! to [`capture`] wait for each other.
+//! Output written by other threads while a redirection is active ends up in that capture too.
+//! That includes formatters writing to [`io::stdout`], use [`original_stdout`] as their target
+//! instead.
+//!
+//! Use [`FdCaptureRunner`](crate::runner::FdCaptureRunner) to capture the output of every test.
+
+use std::{
+    ffi::{c_int, c_void},
+    fs::File,
+    io::{self, PipeReader, Read, Write},
+    os::fd::{AsRawFd, BorrowedFd, OwnedFd},
+    ptr,
+    sync::{Mutex, mpsc},
+    thread::{self, JoinHandle},
+};
+
+use crate::capture::{OutputCapture, OutputTarget};
+
+unsafe extern "C" {
+    fn dup2(oldfd: c_int, newfd: c_int) -> c_int;
+    fn fflush(stream: *mut c_void) -> c_int;
+}
+
+const STDOUT_FD: c_int = 1;
+const STDERR_FD: c_int = 2;
+
+static REDIRECT_LOCK: Mutex<()> = Mutex::new(());
+
+/// Run `f` while the process stdout and stderr are redirected and return the captured output.
+///
+/// Everything written to the file descriptors 1 and 2 while `f` runs is captured, no matter if
+/// it was written by Rust code, C code or a subprocess inheriting these descriptors.
+/// Only one redirection is active at a time, other calls block until `f` returned.
+///
+/// The original descriptors are restored even if `f` panics.
+/// Subprocesses that outlive `f` keep the pipes open, so this function waits for them to exit or
+/// close their output.
+///
+/// # Errors
+///
+/// Returns an error if the pipes could not be created or the descriptors could not be
+/// redirected.
+/// In that case, `f` is not called.
+pub fn capture<T>(f: impl FnOnce() -> T) -> io::Result<(T, OutputCapture)> {
+    let _lock = REDIRECT_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let (tx, rx) = mpsc::channel();
+    let stdout = Redirect::install(STDOUT_FD, OutputTarget::Stdout, tx.clone())?;
+    let stderr = Redirect::install(STDERR_FD, OutputTarget::Stderr, tx)?;
+
+    let value = f();
+
+    stdout.restore()?;
+    stderr.restore()?;
+
+    let mut capture = OutputCapture::new();
+    for (target, chunk) in rx {
+        capture.push_event(&chunk, target);
+    }
+    Ok((value, capture))
+}
+
+/// Open a handle to the stdout of the process that bypasses any active redirection.
+///
+/// The handle duplicates file descriptor 1 at the time of the call.
+/// Call this before any redirection is active, for example while building the harness, and
+/// pass it to a formatter via `with_target`.
+/// This keeps formatter output on the terminal while [`capture`] redirects stdout.
+///
+/// # Errors
+///
+/// Returns an error if the file descriptor could not be duplicated.
+pub fn original_stdout() -> io::Result<File> {
+    duplicate(STDOUT_FD).map(File::from)
+}
+
+fn duplicate(fd: c_int) -> io::Result<OwnedFd> {
+    // SAFETY: the standard streams stay open for the whole lifetime of the process
+    unsafe { BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()
+}
+
+fn redirect(from: c_int, to: c_int) -> io::Result<()> {
+    loop {
+        // SAFETY: both descriptors are valid, `dup2` does not touch any memory
+        match unsafe { dup2(from, to) } {
+            -1 => match io::Error::last_os_error() {
+                err if err.kind() == io::ErrorKind::Interrupted => continue,
+                err => return Err(err),
+            },
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn flush_all() {
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+    // SAFETY: passing a null pointer flushes all open C streams
+    unsafe { fflush(ptr::null_mut()) };
+}
+
+struct Redirect {
+    fd: c_int,
+    original: Option<OwnedFd>,
+    drain: Option<JoinHandle<()>>,
+}
+
+impl Redirect {
+    fn install(
+        fd: c_int,
+        target: OutputTarget,
+        tx: mpsc::Sender<(OutputTarget, Vec<u8>)>,
+    ) -> io::Result<Self> {
+        flush_all();
+        let original = duplicate(fd)?;
+        let (reader, writer) = io::pipe()?;
+        redirect(writer.as_raw_fd(), fd)?;
+        // `fd` now refers to the write end, dropping `writer` leaves it as the only one
+        drop(writer);
+
+        let drain = thread::Builder::new()
+            .name(String::from("kitest-fd-capture"))
+            .spawn(move || drain(reader, target, tx))?;
+
+        Ok(Self {
+            fd,
+            original: Some(original),
+            drain: Some(drain),
+        })
+    }
+
+    fn restore(mut self) -> io::Result<()> {
+        self.restore_fd()
+    }
+
+    fn restore_fd(&mut self) -> io::Result<()> {
+        let Some(original) = self.original.take() else {
+            return Ok(());
+        };
+
+        flush_all();
+        let res = redirect(original.as_raw_fd(), self.fd);
+        if let Some(drain) = self.drain.take() {
+            // the drain thread returns once the last write end is closed
+            let _ = drain.join();
+        }
+        res
+    }
+}
+
+impl Drop for Redirect {
+    fn drop(&mut self) {
+        let _ = self.restore_fd();
+    }
+}
+
+fn drain(mut reader: PipeReader, target: OutputTarget, tx: mpsc::Sender<(OutputTarget, Vec<u8>)>) {
+    let mut buf = [0; 4096];
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                if tx.send((target, buf[..n].to_vec())).is_err() {
+                    return;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Capture of records of the [`log`] crate.
+//!
+//! Code that logs through the `log` facade does not use Kitest's capture aware macros.
+//! [`CaptureLogger`] is a [`Log`] implementation that writes every record into the
+//! [`TEST_OUTPUT_CAPTURE`] of the thread that logged it, tagged with level and target.
+//! Records therefore end up in the captured stderr of the test that logged them.
+//!
+//! The maximum level can be changed per thread with [`set_max_level`], or per test with
+//! [`MaxLevelScope`].
+//!
+//! This module is only available with the `log` feature.
+
+use std::{cell::Cell, io::Write};
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::{
+    capture::TEST_OUTPUT_CAPTURE,
+    outcome::TestOutcome,
+    runner::scope::{TestScope, TestScopeFactory},
+    test::TestMeta,
+};
+
+thread_local! {
+    static MAX_LEVEL: Cell<Option<LevelFilter>> = const { Cell::new(None) };
+}
+
+/// Override the maximum level of [`CaptureLogger`] for the current thread.
+///
+/// Pass `None` to fall back to the level of the logger.
+/// The override stays until it is changed again, use [`MaxLevelScope`] to reset it after each
+/// test.
+pub fn set_max_level(level: Option<LevelFilter>) {
+    MAX_LEVEL.set(level);
+}
+
+/// A [`Log`] implementation writing records into the output capture of the logging thread.
+///
+/// Each record is written as one line to the captured stderr:
+///
+/// ```text
+/// [INFO  my_crate::module] message
+/// ```
+///
+/// Install it once at the start of the test binary using [`install`](Self::install).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureLogger {
+    max_level: LevelFilter,
+}
+
+impl Default for CaptureLogger {
+    fn default() -> Self {
+        Self {
+            max_level: LevelFilter::Trace,
+        }
+    }
+}
+
+impl CaptureLogger {
+    /// Create a logger capturing records of all levels.
+    ///
+    /// This is the same as `CaptureLogger::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum level of captured records.
+    ///
+    /// Threads can override this via [`set_max_level`].
+    pub fn with_max_level(self, max_level: LevelFilter) -> Self {
+        Self { max_level }
+    }
+
+    /// Install this logger as the global logger.
+    ///
+    /// This also raises the global maximum level of the `log` crate to
+    /// [`Trace`](LevelFilter::Trace), so the per thread overrides can enable any level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a global logger was already installed.
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        log::set_logger(Box::leak(Box::new(self)))?;
+        log::set_max_level(LevelFilter::Trace);
+        Ok(())
+    }
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.level() <= MAX_LEVEL.get().unwrap_or(self.max_level)
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        // logging may happen while the capture is borrowed or the thread shuts down
+        let _ = TEST_OUTPUT_CAPTURE.try_with(|capture| {
+            if let Ok(mut capture) = capture.try_borrow_mut() {
+                writeln!(
+                    capture.stderr(),
+                    "[{:<5} {}] {}",
+                    record.level(),
+                    record.target(),
+                    record.args()
+                )
+                .expect("infallible for Vec<u8>");
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// A [`TestScopeFactory`] setting the maximum log level for each test.
+///
+/// Before a test runs, the level is chosen by calling the wrapped function with the test
+/// metadata and applied via [`set_max_level`].
+/// After the test, the override is removed again.
+#[derive(Debug, Clone)]
+pub struct MaxLevelScope<F>(pub F);
+
+impl<'t, Extra, F> TestScopeFactory<'t, Extra> for MaxLevelScope<F>
+where
+    F: Fn(&TestMeta<Extra>) -> LevelFilter,
+{
+    type Scope<'f>
+        = &'f Self
+    where
+        't: 'f,
+        Self: 'f;
+
+    fn make_scope<'f>(&'f self) -> Self::Scope<'f>
+    where
+        't: 'f,
+    {
+        self
+    }
+}
+
+impl<'t, Extra, F> TestScope<'t, Extra> for &MaxLevelScope<F>
+where
+    F: Fn(&TestMeta<Extra>) -> LevelFilter,
+{
+    fn before_test(&mut self, meta: &'t TestMeta<Extra>) {
+        set_max_level(Some((self.0)(meta)));
+    }
+
+    fn after_test(&mut self, _: &'t TestMeta<Extra>, _: &TestOutcome) {
+        set_max_level(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{runner::SimpleRunner, test_support::*};
+
+    fn log_all() {
+        log::error!("error");
+        log::info!("info");
+        log::trace!("trace");
+    }
+
+    #[test]
+    fn captures_records_with_max_level_per_test() {
+        let _ = CaptureLogger::new()
+            .with_max_level(LevelFilter::Info)
+            .install();
+
+        let tests = &[
+            test! {name: "default", func: log_all},
+            test! {name: "quiet", func: log_all},
+            test! {name: "verbose", func: log_all},
+        ];
+
+        let report = harness(tests)
+            .with_runner(
+                SimpleRunner::default().with_test_scope_factory(MaxLevelScope(
+                    |meta: &TestMeta| match meta.name.as_ref() {
+                        "quiet" => LevelFilter::Error,
+                        "verbose" => LevelFilter::Trace,
+                        _ => LevelFilter::Info,
+                    },
+                )),
+            )
+            .run();
+
+        let stderr: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| {
+                outcome
+                    .output
+                    .read_stderr()
+                    .fold(String::new(), |s, chunk| {
+                        s + &String::from_utf8_lossy(chunk)
+                    })
+            })
+            .collect();
+
+        let target = module_path!();
+        assert_eq!(
+            stderr[0],
+            format!("[ERROR {target}] error\n[INFO  {target}] info\n")
+        );
+        assert_eq!(stderr[1], format!("[ERROR {target}] error\n"));
+        assert_eq!(
+            stderr[2],
+            format!("[ERROR {target}] error\n[INFO  {target}] info\n[TRACE {target}] trace\n")
+        );
+        assert_eq!(MAX_LEVEL.get(), None);
+    }
+}
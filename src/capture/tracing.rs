@@ -0,0 +1,325 @@
+//! Capture of events of the [`tracing`] crate.
+//!
+//! Two pieces make tests play well with `tracing`:
+//! - [`CaptureLayer`] is a subscriber [`Layer`] that formats every event into the
+//!   [`TEST_OUTPUT_CAPTURE`] of the thread that emitted it.
+//!   Events therefore end up in the captured stderr of the test that emitted them.
+//! - [`TestSpanScope`] is a [`TestScopeFactory`] that runs every test inside a `test` span carrying
+//!   the test name, group key and origin as fields.
+//!   Pass it to [`DefaultRunner`](crate::runner::DefaultRunner) or
+//!   [`SimpleRunner`](crate::runner::SimpleRunner) via `with_test_scope_factory`.
+//!
+//! Span names in `tracing` are static, so the test name is recorded in the `name` field of the
+//! span instead.
+//!
+//! This module is only available with the `tracing` feature.
+
+use std::{
+    fmt::{self, Display, Write as _},
+    io::Write as _,
+};
+
+use tracing::{
+    Event, Subscriber,
+    field::{Empty, Field, Visit},
+    span::EnteredSpan,
+};
+use tracing_subscriber::{Layer, layer::Context};
+
+use crate::{
+    capture::TEST_OUTPUT_CAPTURE,
+    outcome::TestOutcome,
+    runner::scope::{TestScope, TestScopeFactory},
+    test::TestMeta,
+};
+
+/// A [`Layer`] writing events into the output capture of the emitting thread.
+///
+/// Each event is written as one line to the captured stderr, fields other than the message are
+/// appended as `key=value`:
+///
+/// ```text
+/// [INFO  my_crate::module] message key=value
+/// ```
+///
+/// Compose it with any subscriber supporting layers, for example the `Registry` of
+/// `tracing-subscriber`, and install that subscriber as the global default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureLayer;
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        // events may be emitted while the capture is borrowed or the thread shuts down
+        let _ = TEST_OUTPUT_CAPTURE.try_with(|capture| {
+            if let Ok(mut capture) = capture.try_borrow_mut() {
+                writeln!(
+                    capture.stderr(),
+                    "[{:<5} {}] {}{}",
+                    metadata.level(),
+                    metadata.target(),
+                    visitor.message,
+                    visitor.fields
+                )
+                .expect("infallible for Vec<u8>");
+            }
+        });
+    }
+}
+
+#[derive(Debug, Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        let _ = match field.name() {
+            "message" => write!(self.message, "{value:?}"),
+            name => write!(self.fields, " {name}={value:?}"),
+        };
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message.push_str(value),
+            _ => self.record_debug(field, &value),
+        }
+    }
+}
+
+/// Source of the group key recorded by [`TestSpanScope`].
+///
+/// Implemented for [`NoGroupKey`] and for closures `Fn(&TestMeta<Extra>) -> impl Display`, which
+/// typically mirror the grouper of the harness.
+pub trait SpanGroupKey<Extra> {
+    /// Return the group key of the test, if any.
+    fn group_key(&self, meta: &TestMeta<Extra>) -> Option<String>;
+}
+
+/// A [`SpanGroupKey`] that leaves the group key empty.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoGroupKey;
+
+impl<Extra> SpanGroupKey<Extra> for NoGroupKey {
+    fn group_key(&self, _: &TestMeta<Extra>) -> Option<String> {
+        None
+    }
+}
+
+impl<Extra, F, K> SpanGroupKey<Extra> for F
+where
+    F: Fn(&TestMeta<Extra>) -> K,
+    K: Display,
+{
+    fn group_key(&self, meta: &TestMeta<Extra>) -> Option<String> {
+        Some(self(meta).to_string())
+    }
+}
+
+/// A [`TestScopeFactory`] running every test inside a `test` span.
+///
+/// The span is created at [`INFO`](tracing::Level::INFO) level with the fields `name`, `group`
+/// and `origin`.
+/// It is entered right before the test runs and exited right after, on the thread executing the
+/// test.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TestSpanScope<GroupKey = NoGroupKey> {
+    group_key: GroupKey,
+}
+
+impl TestSpanScope {
+    /// Create a scope factory that does not record a group key.
+    ///
+    /// This is the same as `TestSpanScope::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<GroupKey> TestSpanScope<GroupKey> {
+    /// Record a group key for every test.
+    ///
+    /// This replaces the previous group key source.
+    pub fn with_group_key<WithGroupKey>(
+        self,
+        group_key: WithGroupKey,
+    ) -> TestSpanScope<WithGroupKey> {
+        TestSpanScope { group_key }
+    }
+}
+
+impl<'t, Extra, GroupKey> TestScopeFactory<'t, Extra> for TestSpanScope<GroupKey>
+where
+    GroupKey: SpanGroupKey<Extra>,
+{
+    type Scope<'f>
+        = TestSpan<'f, GroupKey>
+    where
+        't: 'f,
+        Self: 'f;
+
+    fn make_scope<'f>(&'f self) -> Self::Scope<'f>
+    where
+        't: 'f,
+    {
+        TestSpan {
+            factory: self,
+            entered: None,
+        }
+    }
+}
+
+/// The [`TestScope`] created by [`TestSpanScope`].
+#[derive(Debug)]
+pub struct TestSpan<'f, GroupKey> {
+    factory: &'f TestSpanScope<GroupKey>,
+    entered: Option<EnteredSpan>,
+}
+
+impl<'t, Extra, GroupKey> TestScope<'t, Extra> for TestSpan<'_, GroupKey>
+where
+    GroupKey: SpanGroupKey<Extra>,
+{
+    fn before_test(&mut self, meta: &'t TestMeta<Extra>) {
+        let span = tracing::info_span!("test", name = %meta.name, group = Empty, origin = Empty);
+        if let Some(group) = self.factory.group_key.group_key(meta) {
+            span.record("group", group);
+        }
+        if let Some(origin) = &meta.origin {
+            span.record("origin", tracing::field::display(origin));
+        }
+        self.entered = Some(span.entered());
+    }
+
+    fn after_test(&mut self, _: &'t TestMeta<Extra>, _: &TestOutcome) {
+        self.entered = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+    use crate::{
+        runner::{DefaultRunner, SimpleRunner},
+        test_support::*,
+    };
+
+    /// Collects the fields of all `test` spans.
+    #[derive(Clone, Default)]
+    struct SpanFields(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for SpanFields {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+            let mut visitor = EventVisitor::default();
+            attrs.record(&mut visitor);
+            self.0.lock().unwrap().push(visitor.fields);
+        }
+
+        fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+            let mut visitor = EventVisitor::default();
+            values.record(&mut visitor);
+            self.0
+                .lock()
+                .unwrap()
+                .last_mut()
+                .unwrap()
+                .push_str(&visitor.fields);
+        }
+    }
+
+    fn emit() {
+        assert_eq!(
+            tracing::Span::current().metadata().map(|meta| meta.name()),
+            Some("test")
+        );
+        tracing::warn!(answer = 42, "hello");
+    }
+
+    #[test]
+    fn events_are_captured_inside_test_span() {
+        let spans = SpanFields::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(CaptureLayer)
+            .with(spans.clone());
+
+        let tests = &[test! {name: "a", func: emit}, test! {name: "b", func: emit}];
+
+        let (simple, default) = tracing::subscriber::with_default(subscriber, || {
+            let simple = harness(tests)
+                .with_runner(SimpleRunner::default().with_test_scope_factory(
+                    TestSpanScope::new().with_group_key(|meta: &TestMeta| meta.name.len()),
+                ))
+                .run();
+            let dispatch = tracing::dispatcher::get_default(Clone::clone);
+            let default = harness(tests)
+                .with_runner(
+                    DefaultRunner::default()
+                        .with_thread_count(nonzero!(2))
+                        .with_test_scope_factory(TestSpanScope::new())
+                        .with_worker_scope(DispatchWorkerScope(dispatch)),
+                )
+                .run();
+            (simple, default)
+        });
+
+        for (_, outcome) in simple.outcomes.iter().chain(default.outcomes.iter()) {
+            assert!(outcome.passed());
+            let stderr = outcome
+                .output
+                .read_stderr()
+                .fold(String::new(), |s, chunk| {
+                    s + &String::from_utf8_lossy(chunk)
+                });
+            assert_eq!(
+                stderr,
+                format!("[WARN  {}] hello answer=42\n", module_path!())
+            );
+        }
+
+        let mut spans = spans.0.lock().unwrap().clone();
+        spans.sort();
+        assert_eq!(spans.len(), 4);
+        assert!(spans[0].starts_with(" name=a group=\"1\" origin="));
+        assert!(spans[0].contains(file!()));
+        assert!(spans[1].starts_with(" name=a origin="));
+        assert!(spans[3].starts_with(" name=b origin="));
+    }
+
+    /// Makes the dispatcher of the test thread the default on every worker.
+    struct DispatchWorkerScope(tracing::Dispatch);
+
+    impl<'t> crate::runner::scope::WorkerScope<'t, ()> for DispatchWorkerScope {
+        type State = DispatchGuard;
+
+        fn on_worker_start(&self, _: usize) -> Self::State {
+            DispatchGuard(tracing::dispatcher::set_default(&self.0))
+        }
+    }
+
+    struct DispatchGuard(#[allow(dead_code)] tracing::dispatcher::DefaultGuard);
+
+    impl<'t> TestScopeFactory<'t, ()> for DispatchGuard {
+        type Scope<'f>
+            = crate::runner::scope::NoScope
+        where
+            't: 'f,
+            Self: 'f;
+
+        fn make_scope<'f>(&'f self) -> Self::Scope<'f>
+        where
+            't: 'f,
+        {
+            crate::runner::scope::NoScope
+        }
+    }
+}
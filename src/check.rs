@@ -0,0 +1,284 @@
+//! Soft assertions.
+//!
+//! A failing [`assert!`] panics, so a test stops at the first mismatch.
+//! The [`check!`](crate::check!) and [`check_eq!`](crate::check_eq) macros record a failure
+//! instead and let the test continue.
+//! Once the test returns, all recorded failures fail the test together with
+//! [`TestFailure::Multiple`](crate::outcome::TestFailure::Multiple), each with the location of the
+//! failed check.
+//!
+//! Failures are recorded into the [`CheckHandle`] of the current thread.
+//! Threads spawned through [`kitest::thread`](crate::thread) share the handle of the spawning
+//! thread, so their failed checks fail the test that spawned them.
+//! A check failing on a thread without a handle panics, since no test would ever see it.
+//! If the test panics, the recorded failures are discarded and the panic is reported instead.
+
+use std::{
+    cell::RefCell,
+    fmt::Display,
+    mem,
+    panic::Location,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{Whatever, capture::PanicLocation, test::TestResult};
+
+thread_local! {
+    static CHECK_HANDLE: RefCell<Option<CheckHandle>> = const { RefCell::new(None) };
+}
+
+/// A shared list of failed checks of a test.
+///
+/// While a test runs, its thread records failed checks into a handle.
+/// Obtain it with [`CheckHandle::current`] and [`install`](CheckHandle::install) it on other
+/// threads to record their failed checks for the same test.
+///
+/// The functions in [`kitest::thread`](crate::thread) do this automatically.
+#[derive(Debug, Clone, Default)]
+pub struct CheckHandle(Arc<Mutex<Vec<CheckFailure>>>);
+
+impl CheckHandle {
+    /// Return the handle failed checks of the current thread are recorded in, if any.
+    pub fn current() -> Option<Self> {
+        CHECK_HANDLE.with_borrow(Clone::clone)
+    }
+
+    /// Record all failed checks of the current thread into this handle.
+    pub fn install(&self) {
+        CHECK_HANDLE.set(Some(self.clone()));
+    }
+
+    fn failures(&self) -> MutexGuard<'_, Vec<CheckFailure>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Restores the previous handle of the thread once a test is done.
+struct Installed(Option<CheckHandle>);
+
+impl Drop for Installed {
+    fn drop(&mut self) {
+        CHECK_HANDLE.set(self.0.take());
+    }
+}
+
+/// A failed soft assertion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckFailure {
+    /// The failure message.
+    pub message: String,
+    /// Where the check failed.
+    ///
+    /// This is `None` for an error the test returned after checks had failed.
+    pub location: Option<PanicLocation>,
+}
+
+impl Display for CheckFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{location}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Record a failed check for the test running on the current thread.
+///
+/// The location of the caller is recorded with the message.
+/// This is what [`check!`](crate::check!) and [`check_eq!`](crate::check_eq) call on failure, use
+/// it to build custom soft assertions.
+///
+/// # Panics
+///
+/// Panics with the failure if the current thread has no [`CheckHandle`], for example a thread
+/// spawned via [`std::thread`] instead of [`kitest::thread`](crate::thread).
+#[track_caller]
+pub fn record_failure(message: String) {
+    let failure = CheckFailure {
+        message,
+        location: Some(PanicLocation::from(Location::caller())),
+    };
+    match CheckHandle::current() {
+        Some(handle) => handle.failures().push(failure),
+        None => panic!("check failed outside of a test collecting checks: {failure}"),
+    }
+}
+
+/// Remove and return all failed checks recorded for the current thread.
+pub fn take_failures() -> Vec<CheckFailure> {
+    CheckHandle::current()
+        .map(|handle| mem::take(&mut *handle.failures()))
+        .unwrap_or_default()
+}
+
+/// The failed checks of a test, carried as error of its [`TestResult`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckFailures(pub Vec<CheckFailure>);
+
+impl Display for CheckFailures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for failure in &self.0 {
+            writeln!(f, "{failure}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `f` as a test, turning failed checks into an error result.
+pub(crate) fn collect(f: impl FnOnce() -> TestResult) -> TestResult {
+    let handle = CheckHandle::default();
+    let installed = Installed(CHECK_HANDLE.replace(Some(handle.clone())));
+    let result = f();
+    drop(installed);
+    let mut failures = mem::take(&mut *handle.failures());
+    if failures.is_empty() {
+        return result;
+    }
+
+    if let Err(err) = result.0 {
+        let message = match err.as_error() {
+            Some(error) => format!("Error: {error:?}"),
+            None => format!("Error: {err}"),
+        };
+        failures.push(CheckFailure {
+            message,
+            location: None,
+        });
+    }
+    TestResult(Err(Whatever::from(CheckFailures(failures))))
+}
+
+/// Check that a condition holds without stopping the test.
+///
+/// Like [`assert!`], but a failure is recorded via [`record_failure`] and the test continues.
+/// Custom messages are formatted like [`format!`].
+/// Evaluates to whether the condition held.
+///
+/// ```
+/// # fn test() {
+/// let name = "kitest";
+/// kitest::check!(name.starts_with("ki"));
+/// kitest::check!(name.len() == 6, "unexpected length of {name:?}");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! check {
+    ($cond:expr $(,)?) => {{
+        let ok: bool = $cond;
+        if !ok {
+            $crate::check::record_failure(::std::format!(
+                "check failed: {}",
+                ::std::stringify!($cond)
+            ));
+        }
+        ok
+    }};
+    ($cond:expr, $($arg:tt)+) => {{
+        let ok: bool = $cond;
+        if !ok {
+            $crate::check::record_failure(::std::format!($($arg)+));
+        }
+        ok
+    }};
+}
+
+/// Check that two expressions are equal without stopping the test.
+///
+/// Like [`assert_eq!`], but a failure is recorded via [`record_failure`] and the test continues.
+/// Evaluates to whether both values were equal.
+#[macro_export]
+macro_rules! check_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let ok = *left == *right;
+                if !ok {
+                    $crate::check::record_failure(::std::format!(
+                        "check `left == right` failed\n  left: {left:?}\n right: {right:?}"
+                    ));
+                }
+                ok
+            }
+        }
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let ok = *left == *right;
+                if !ok {
+                    $crate::check::record_failure(::std::format!(
+                        "check `left == right` failed: {}\n  left: {left:?}\n right: {right:?}",
+                        ::std::format_args!($($arg)+)
+                    ));
+                }
+                ok
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        outcome::{TestFailure, TestStatus},
+        test_support::*,
+    };
+
+    #[test]
+    fn failed_checks_are_collected() {
+        let line = line!() + 4;
+        let tests = &[
+            test! {name: "ok", func: || { crate::check!(true); }},
+            test! {name: "soft", func: || {
+                crate::check!(1 + 1 == 3);
+                crate::check_eq!(2, 3, "numbers");
+                crate::check!(false, "custom {}", 42);
+            }},
+            test! {name: "error", func: || {
+                crate::check!(false);
+                Err::<(), _>("oh no")
+            }},
+        ];
+
+        let report = harness(tests).run();
+        let outcomes = &report.outcomes;
+        assert!(outcomes[0].1.passed());
+
+        let TestStatus::Failed(TestFailure::Multiple(failures)) = &outcomes[1].1.status else {
+            panic!("expected multiple failures, got {:?}", outcomes[1].1.status);
+        };
+        let messages: Vec<_> = failures.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "check failed: 1 + 1 == 3",
+                "check `left == right` failed: numbers\n  left: 2\n right: 3",
+                "custom 42",
+            ]
+        );
+        let lines: Vec<_> = failures
+            .iter()
+            .map(|f| f.location.as_ref().unwrap().line)
+            .collect();
+        assert_eq!(lines, [line, line + 1, line + 2]);
+        assert!(
+            failures
+                .iter()
+                .all(|f| f.location.as_ref().unwrap().file == file!())
+        );
+
+        let TestStatus::Failed(TestFailure::Multiple(failures)) = &outcomes[2].1.status else {
+            panic!("expected multiple failures, got {:?}", outcomes[2].1.status);
+        };
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[1].message, "Error: \"oh no\"");
+        assert_eq!(failures[1].location, None);
+        assert!(super::take_failures().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "check failed outside of a test collecting checks")]
+    fn checks_outside_of_tests_panic() {
+        crate::check!(false);
+    }
+}
@@ -0,0 +1,189 @@
+//! Driving async tests.
+//!
+//! Kitest executes every test synchronously through [`TestFnHandle::call`](crate::test::TestFnHandle::call).
+//! To support async test functions, the returned future has to be driven to completion on the
+//! thread that executes the test.
+//!
+//! This module provides:
+//! - the [`Executor`] trait, a small hook to plug in any executor that can block on a future
+//! - [`DefaultExecutor`], a minimal executor only using the standard library
+//! - [`block_on`], the function that powers [`DefaultExecutor`]
+//!
+//! Because the future is driven on the calling thread, panics raised while polling unwind through
+//! [`block_on`] like any other panic.
+//! A [`TestPanicHandler`](crate::panic::TestPanicHandler) therefore treats async tests exactly the
+//! same as regular ones.
+//!
+//! [`DefaultExecutor`] does not provide any IO or timer drivers.
+//! Futures that depend on a specific runtime (for example `tokio`) need an [`Executor`]
+//! implementation backed by that runtime, see
+//! [`TestFnHandle::from_future_fn_with`](crate::test::TestFnHandle::from_future_fn_with).
+
+use std::{
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+/// A strategy for driving a future to completion on the current thread.
+///
+/// Async tests are executed by handing their future to an executor.
+/// The executor must block until the future resolves and return its output.
+///
+/// Implement this trait to run async tests on a specific runtime, for example by delegating to
+/// the `block_on` method of that runtime.
+pub trait Executor {
+    /// Block the current thread until `future` resolves and return its output.
+    fn block_on<F: Future>(&self, future: F) -> F::Output;
+}
+
+/// The default [`Executor`] implementation.
+///
+/// This executor parks the current thread while the future is pending and unparks it once the
+/// future's waker is called.
+/// It has no dependencies outside of the standard library, see [`block_on`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultExecutor;
+
+impl Executor for DefaultExecutor {
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        block_on(future)
+    }
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Block the current thread until `future` resolves.
+///
+/// The future is polled on the current thread.
+/// Whenever it returns [`Poll::Pending`], the thread is parked until the waker is called.
+/// Spurious wake ups only lead to an additional poll.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{
+        panic::DefaultPanicHandler, runner::SimpleRunner, test::TestFnHandle, test_support::*,
+    };
+
+    /// Future that is pending until another thread flips the flag and wakes it.
+    struct WakeFromThread(Arc<AtomicBool>, bool);
+
+    impl Future for WakeFromThread {
+        type Output = u32;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            if self.0.load(Ordering::Acquire) {
+                return Poll::Ready(42);
+            }
+
+            if !self.1 {
+                self.1 = true;
+                let flag = Arc::clone(&self.0);
+                let waker = cx.waker().clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(10));
+                    flag.store(true, Ordering::Release);
+                    waker.wake();
+                });
+            }
+
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn block_on_ready_future() {
+        assert_eq!(block_on(async { 1 + 1 }), 2);
+    }
+
+    #[test]
+    fn block_on_waits_for_wake() {
+        let future = WakeFromThread(Arc::new(AtomicBool::new(false)), false);
+        assert_eq!(block_on(future), 42);
+    }
+
+    #[test]
+    fn async_tests_work_with_panic_handler() {
+        let tests = &[
+            test! {
+                name: "ok",
+                func: TestFnHandle::from_future_fn(|| async {})
+            },
+            test! {
+                name: "err",
+                func: TestFnHandle::from_future_fn(|| async { Err::<(), _>("oh no") })
+            },
+            test! {
+                name: "panic",
+                func: TestFnHandle::from_future_fn(|| async { if true { panic!("oh no") } })
+            },
+            test! {
+                name: "should_panic",
+                should_panic: "oh no",
+                func: TestFnHandle::from_future_fn(|| async { if true { panic!("oh no") } })
+            },
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+        let outcomes = report.outcomes;
+
+        assert!(outcomes[0].1.passed());
+        assert!(outcomes[1].1.failed());
+        assert!(outcomes[2].1.failed());
+        assert!(outcomes[3].1.passed());
+    }
+
+    #[test]
+    fn custom_executor_is_used() {
+        static USED: AtomicBool = AtomicBool::new(false);
+
+        struct RecordingExecutor;
+
+        impl Executor for RecordingExecutor {
+            fn block_on<F: Future>(&self, future: F) -> F::Output {
+                USED.store(true, Ordering::Relaxed);
+                block_on(future)
+            }
+        }
+
+        let tests = &[test! {
+            func: TestFnHandle::from_future_fn_with(RecordingExecutor, || async {})
+        }];
+
+        let report = harness(tests).run();
+        assert!(report.outcomes[0].1.passed());
+        assert!(USED.load(Ordering::Relaxed));
+    }
+}
@@ -1,6 +1,6 @@
 //! Format transfer objects that are used by multiple formatters.
 
-use std::{fmt::Display, marker::PhantomData};
+use std::{borrow::Cow, fmt::Display, marker::PhantomData};
 
 use crate::{capture::OutputCapture, formatter::*, outcome::*};
 
@@ -47,17 +47,45 @@ impl<'t, Extra> From<FmtListTest<'t, Extra>> for TestName<'t> {
     }
 }
 
+/// A filtered out test and the reason why, from [`FmtListFilteredOut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestFilteredOut<'t> {
+    pub name: &'t str,
+    pub reason: Cow<'static, str>,
+}
+
+impl<'t, 'r, Extra> From<FmtListFilteredOut<'t, 'r, Extra>> for TestFilteredOut<'t> {
+    fn from(value: FmtListFilteredOut<'t, 'r, Extra>) -> Self {
+        Self {
+            name: value.meta.name.as_ref(),
+            reason: value.reason.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct RunOutcomes<'t> {
     pub passed: usize,
     pub failed: usize,
     pub ignored: usize,
+    pub expected_failures: usize,
+    pub unexpected_passes: usize,
+    pub quarantined: usize,
+    pub quarantined_passes: Vec<QuarantinedPass<'t>>,
     pub filtered_out: usize,
     pub duration: Duration,
     pub failures: Vec<Failure<'t>>,
 }
 
+/// A quarantined test that passed and should leave the quarantine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct QuarantinedPass<'t> {
+    pub group: Option<String>,
+    pub name: &'t str,
+}
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct Failure<'t> {
@@ -88,6 +116,30 @@ impl<'t, 'o> From<FmtRunOutcomes<'t, 'o>> for RunOutcomes<'t> {
                 .map(|(_, outcome)| outcome)
                 .filter(|outcome| outcome.ignored())
                 .count(),
+            expected_failures: value
+                .outcomes
+                .iter()
+                .map(|(_, outcome)| outcome)
+                .filter(|outcome| outcome.expected_failure())
+                .count(),
+            unexpected_passes: value
+                .outcomes
+                .iter()
+                .map(|(_, outcome)| outcome)
+                .filter(|outcome| outcome.unexpected_pass())
+                .count(),
+            quarantined: value
+                .outcomes
+                .iter()
+                .map(|(_, outcome)| outcome)
+                .filter(|outcome| outcome.quarantined())
+                .count(),
+            quarantined_passes: value
+                .outcomes
+                .iter()
+                .filter(|(_, outcome)| outcome.quarantined_pass())
+                .map(|(name, _)| QuarantinedPass { group: None, name })
+                .collect(),
             filtered_out: value.filtered_out,
             duration: value.duration,
             failures: value
@@ -115,6 +167,9 @@ impl<'t, 'o> From<FmtRunOutcomes<'t, 'o>> for RunOutcomes<'t> {
 pub struct GroupStart<L> {
     pub tests: usize,
     pub name: String,
+    pub index: usize,
+    /// Whether other groups may run at the same time.
+    pub concurrent: bool,
     pub _label_marker: PhantomData<L>,
 }
 
@@ -128,6 +183,8 @@ where
         Self {
             name: label,
             tests: value.tests,
+            index: value.index,
+            concurrent: value.group_concurrency.get() > 1,
             _label_marker: PhantomData,
         }
     }
@@ -140,6 +197,10 @@ pub struct GroupedRunOutcomes<'t, L> {
     pub passed: usize,
     pub failed: usize,
     pub ignored: usize,
+    pub expected_failures: usize,
+    pub unexpected_passes: usize,
+    pub quarantined: usize,
+    pub quarantined_passes: Vec<QuarantinedPass<'t>>,
     pub filtered_out: usize,
     pub duration: Duration,
     pub failures: Vec<Failure<'t>>,
@@ -177,6 +238,25 @@ where
             passed: count_outcomes(&value, |outcome| outcome.passed()),
             failed: count_outcomes(&value, |outcome| outcome.failed()),
             ignored: count_outcomes(&value, |outcome| outcome.ignored()),
+            expected_failures: count_outcomes(&value, |outcome| outcome.expected_failure()),
+            unexpected_passes: count_outcomes(&value, |outcome| outcome.unexpected_pass()),
+            quarantined: count_outcomes(&value, |outcome| outcome.quarantined()),
+            quarantined_passes: value
+                .outcomes
+                .iter()
+                .flat_map(|(group_key, outcomes, group_ctx)| {
+                    outcomes
+                        .iter()
+                        .filter(|(_, outcome)| outcome.quarantined_pass())
+                        .map(move |(name, _)| {
+                            let group = L::from((group_key, group_ctx.as_ref())).to_string();
+                            QuarantinedPass {
+                                group: (!group.is_empty()).then_some(group),
+                                name,
+                            }
+                        })
+                })
+                .collect(),
             filtered_out: 0, // TODO: get proper value here
             duration: value.duration,
             failures: value
@@ -215,6 +295,10 @@ impl<'t, L> GroupedRunOutcomes<'t, L> {
                 passed: self.passed,
                 failed: self.failed,
                 ignored: self.ignored,
+                expected_failures: self.expected_failures,
+                unexpected_passes: self.unexpected_passes,
+                quarantined: self.quarantined,
+                quarantined_passes: self.quarantined_passes,
                 filtered_out: self.filtered_out,
                 duration: self.duration,
                 failures: self.failures,
@@ -4,7 +4,7 @@
 //! They are intentionally formatter focused and are not meant to be general purpose building blocks
 //! for unrelated code.
 
-use std::{collections::HashMap, fmt::Display, io, marker::PhantomData};
+use std::{collections::HashMap, fmt::Display, io, iter, marker::PhantomData};
 
 use crate::{
     formatter::{
@@ -28,6 +28,8 @@ pub(super) struct CommonFormatter<'t, W: io::Write, L, Extra> {
     pub target: W,
     pub color_setting: ColorSetting,
     pub tests: HashMap<&'t str, &'t Test<Extra>>,
+    /// Labels of the groups that run concurrently, by group index.
+    pub group_labels: HashMap<usize, String>,
     pub _label_marker: PhantomData<L>,
 }
 
@@ -40,6 +42,13 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> CommonFormatter<'t, W, L, Extra
         }
     }
 
+    /// Return the label of the group with `index` if groups run concurrently.
+    ///
+    /// Test lines are prefixed with it, since the tests of several groups interleave.
+    pub fn concurrent_group(&self, index: Option<usize>) -> Option<&str> {
+        self.group_labels.get(&index?).map(String::as_str)
+    }
+
     fn fmt_common_run_outcomes(&mut self, data: &fto::RunOutcomes) -> io::Result<()> {
         if !data.failures.is_empty() {
             writeln!(self.target)?;
@@ -53,8 +62,22 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> CommonFormatter<'t, W, L, Extra
                     None => writeln!(self.target, "---- {} stdout ----", failure.name)?,
                 }
                 match &failure.failure {
-                    TestFailure::Error(err) => writeln!(self.target, "Error: {err}")?,
-                    TestFailure::Panicked(_) => self.target.write_all(failure.output.raw())?,
+                    TestFailure::Error(err) => match err.as_error() {
+                        Some(error) => {
+                            writeln!(self.target, "Error: {error:?}")?;
+                            let sources = iter::successors(error.source(), |e| e.source());
+                            for (i, source) in sources.enumerate() {
+                                if i == 0 {
+                                    writeln!(self.target, "\nCaused by:")?;
+                                }
+                                writeln!(self.target, "    {i}: {source}")?;
+                            }
+                        }
+                        None => writeln!(self.target, "Error: {err}")?,
+                    },
+                    TestFailure::Panicked { .. } => {
+                        self.target.write_all(&failure.output.raw_with_marker())?
+                    }
                     TestFailure::DidNotPanic { .. } => {
                         if let Some(meta) = self.tests.get(failure.name)
                             && let Some(origin) = &meta.origin
@@ -68,16 +91,40 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> CommonFormatter<'t, W, L, Extra
                     TestFailure::PanicMismatch {
                         got: _,
                         expected: None,
+                        ..
                     } => unreachable!("mismatch not possible without expectation"),
                     TestFailure::PanicMismatch {
                         got,
                         expected: Some(expected),
+                        rule,
                     } => {
-                        self.target.write_all(failure.output.raw())?;
-                        writeln!(self.target, "note: panic did not contain expected string")?;
-                        writeln!(self.target, "      panic message: {got:?}")?;
-                        write!(self.target, " expected substring: {expected:?}")?;
+                        self.target.write_all(&failure.output.raw_with_marker())?;
+                        writeln!(self.target, "note: {rule}")?;
+                        writeln!(self.target, "{:>19}: {got:?}", "panic message")?;
+                        write!(self.target, "{:>19}: {expected:?}", rule.expected_label())?;
+                    }
+                    TestFailure::Multiple(failures) => {
+                        self.target.write_all(&failure.output.raw_with_marker())?;
+                        match failures.len() {
+                            1 => writeln!(self.target, "1 check failed:")?,
+                            n => writeln!(self.target, "{n} checks failed:")?,
+                        }
+                        for check in failures {
+                            let check = check.to_string();
+                            let mut lines = check.lines();
+                            if let Some(first) = lines.next() {
+                                write!(self.target, "  - {first}")?;
+                            }
+                            for line in lines {
+                                write!(self.target, "\n    {line}")?;
+                            }
+                            writeln!(self.target)?;
+                        }
                     }
+                    TestFailure::UnexpectedPass => write!(
+                        self.target,
+                        "note: test was expected to fail but passed, remove the expected failure marker"
+                    )?,
                 }
                 writeln!(self.target)?;
             }
@@ -91,6 +138,20 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> CommonFormatter<'t, W, L, Extra
             }
         }
 
+        if !data.quarantined_passes.is_empty() {
+            writeln!(self.target)?;
+            writeln!(
+                self.target,
+                "quarantined tests passed, remove them from the quarantine:"
+            )?;
+            for pass in data.quarantined_passes.iter() {
+                match &pass.group {
+                    Some(group) => writeln!(self.target, "    {group}: {}", pass.name)?,
+                    None => writeln!(self.target, "    {}", pass.name)?,
+                }
+            }
+        }
+
         writeln!(self.target)?;
         write!(self.target, "test result: ")?;
         match (data.failed, self.use_color()) {
@@ -100,6 +161,39 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> CommonFormatter<'t, W, L, Extra
             (_, true) => write!(self.target, "{RED}FAILED{RESET}. "),
         }
     }
+
+    fn fmt_outcome_counts(&mut self, data: &fto::RunOutcomes) -> io::Result<()> {
+        write!(
+            self.target,
+            "{} passed; {} failed; {} ignored; ",
+            data.passed, data.failed, data.ignored
+        )?;
+        if data.expected_failures > 0 {
+            write!(
+                self.target,
+                "{} expected failures; ",
+                data.expected_failures
+            )?;
+        }
+        if data.unexpected_passes > 0 {
+            write!(
+                self.target,
+                "{} unexpected passes; ",
+                data.unexpected_passes
+            )?;
+        }
+        if data.quarantined > 0 {
+            write!(self.target, "{} quarantined; ", data.quarantined)?;
+        }
+        if !data.quarantined_passes.is_empty() {
+            write!(
+                self.target,
+                "{} quarantined passed; ",
+                data.quarantined_passes.len()
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl<'t, Extra> Default for CommonFormatter<'t, io::Stdout, GroupLabel<FromGroupKey>, Extra> {
@@ -108,6 +202,7 @@ impl<'t, Extra> Default for CommonFormatter<'t, io::Stdout, GroupLabel<FromGroup
             target: io::stdout(),
             color_setting: Default::default(),
             tests: Default::default(),
+            group_labels: Default::default(),
             _label_marker: PhantomData,
         }
     }
@@ -139,24 +234,23 @@ where
     fn fmt_run_outcomes(
         &mut self,
         #[allow(clippy::toplevel_ref_arg)] ref data @ fto::RunOutcomes {
-            ref passed,
-            ref failed,
-            ref ignored,
             ref filtered_out,
             ref duration,
             ..
         }: Self::RunOutcomes,
     ) -> Result<(), Self::Error> {
         self.fmt_common_run_outcomes(data)?;
+        self.fmt_outcome_counts(data)?;
         writeln!(
             self.target,
-            "{passed} passed; {failed} failed; {ignored} ignored; 0 measured; {filtered_out} filtered out; finished in {:.2}s",
+            "0 measured; {filtered_out} filtered out; finished in {:.2}s",
             duration.as_secs_f64()
         )?;
         writeln!(self.target)
     }
 
     type TestIgnored = ();
+
     type TestStart = ();
     type TestOutcome = ();
 }
@@ -184,6 +278,9 @@ where
             true => "default",
             false => data.name.as_str(),
         };
+        if data.concurrent {
+            self.group_labels.insert(data.index, group_name.to_string());
+        }
         writeln!(
             self.target,
             "group {group_name}, running {} tests",
@@ -199,9 +296,6 @@ where
         let (
             groups,
             ref data @ fto::RunOutcomes {
-                ref passed,
-                ref failed,
-                ref ignored,
                 ref filtered_out,
                 ref duration,
                 ..
@@ -209,10 +303,11 @@ where
         ) = data.split();
 
         self.fmt_common_run_outcomes(data)?;
+        self.fmt_outcome_counts(data)?;
 
         writeln!(
             self.target,
-            "{passed} passed; {failed} failed; {ignored} ignored; {filtered_out} filtered out; across {groups} groups, finished in {:.2}s",
+            "{filtered_out} filtered out; across {groups} groups, finished in {:.2}s",
             duration.as_secs_f64()
         )?;
         writeln!(self.target)
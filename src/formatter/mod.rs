@@ -71,12 +71,16 @@ pub struct FmtRunStart {
 pub struct FmtTestIgnored<'t, 'r, Extra> {
     pub meta: &'t TestMeta<Extra>,
     pub reason: Option<&'r Cow<'static, str>>,
+    /// The [index](FmtGroupStart::index) of the group of the test, `None` for ungrouped runs.
+    pub group: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct FmtTestStart<'t, Extra> {
     pub meta: &'t TestMeta<Extra>,
+    /// The [index](FmtGroupStart::index) of the group of the test, `None` for ungrouped runs.
+    pub group: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +88,8 @@ pub struct FmtTestStart<'t, Extra> {
 pub struct FmtTestOutcome<'t, 'o, Extra> {
     pub meta: &'t TestMeta<Extra>,
     pub outcome: &'o TestOutcome,
+    /// The [index](FmtGroupStart::index) of the group of the test, `None` for ungrouped runs.
+    pub group: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -174,6 +180,13 @@ pub struct FmtGroupedRunStart {
 pub struct FmtGroupStart<'g, GroupKey, GroupCtx = ()> {
     pub tests: usize,
     pub worker_count: NonZeroUsize,
+    /// The position of the group in the run.
+    ///
+    /// Test events of this group carry the same index, which tells the groups apart when they
+    /// run at the same time.
+    pub index: usize,
+    /// The number of groups that may run at the same time, including this one.
+    pub group_concurrency: NonZeroUsize,
     pub key: &'g GroupKey,
     pub ctx: Option<&'g GroupCtx>,
 }
@@ -183,6 +196,8 @@ pub struct FmtGroupStart<'g, GroupKey, GroupCtx = ()> {
 pub struct FmtGroupOutcomes<'t, 'g, 'o, GroupKey, GroupCtx = ()> {
     pub outcomes: &'o TestOutcomes<'t>,
     pub duration: Duration,
+    /// The [index](FmtGroupStart::index) of the group.
+    pub index: usize,
     pub key: &'g GroupKey,
     pub ctx: Option<&'g GroupCtx>,
 }
@@ -201,6 +216,11 @@ pub struct FmtGroupedRunOutcomes<'t, 'o, GroupKey, GroupCtx> {
 /// (`fmt_test_start`, `fmt_test_outcome`, etc.), and adds group start and end
 /// events around them.
 ///
+/// Groups may run at the same time, see
+/// [`GroupedTestHarness::run_concurrent`](crate::GroupedTestHarness::run_concurrent).
+/// Then the events of different groups interleave, test events carry the
+/// [index](FmtGroupStart::index) of their group to tell them apart.
+///
 /// The grouped run ends with [`Self::fmt_grouped_run_outcomes`], which replaces
 /// [`TestFormatter::fmt_run_outcomes`].
 pub trait GroupedTestFormatter<'t, Extra: 't, GroupKey: 't, GroupCtx: 't = ()>:
@@ -268,6 +288,13 @@ pub struct FmtListTest<'t, Extra> {
     pub ignored: IgnoreStatus,
 }
 
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FmtListFilteredOut<'t, 'r, Extra> {
+    pub meta: &'t TestMeta<Extra>,
+    pub reason: &'r Cow<'static, str>,
+}
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub struct FmtEndListing {
@@ -314,6 +341,16 @@ pub trait TestListFormatter<'t, Extra: 't> {
         discard!(data)
     }
 
+    type ListFilteredOut: for<'r> From<FmtListFilteredOut<'t, 'r, Extra>>;
+    /// Called for each filtered out test when listing in explain mode.
+    ///
+    /// The harness provides [`FmtListFilteredOut`], including test metadata and the reason from
+    /// [`TestFilter::explain`](crate::filter::TestFilter::explain).
+    /// These events follow the listed tests.
+    fn fmt_list_filtered_out(&mut self, data: Self::ListFilteredOut) -> Result<(), Self::Error> {
+        discard!(data)
+    }
+
     type EndListing: From<FmtEndListing>;
     /// Called at the end of listing.
     ///
@@ -420,6 +457,7 @@ make_format_error! {
     FmtInitListing<'t, Extra>: InitListing,
     FmtBeginListing: BeginListing,
     FmtListTest<'t, Extra>: ListTest,
+    FmtListFilteredOut<'t, 'r, Extra>: ListFilteredOut,
     FmtEndListing: EndListing,
     FmtListGroups: ListGroups,
     FmtListGroupStart<'g, GroupKey, GroupCtx>: ListGroupStart,
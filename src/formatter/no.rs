@@ -31,6 +31,7 @@ impl_unit_from![
     FmtInitListing<'t, Extra>,
     FmtBeginListing,
     FmtListTest<'t, Extra>,
+    FmtListFilteredOut<'t, 'r, Extra>,
     FmtEndListing,
     FmtListGroups,
     FmtListGroupStart<'g, GroupKey, GroupCtx>,
@@ -61,6 +62,7 @@ impl<'t, Extra: 't> TestListFormatter<'t, Extra> for NoFormatter {
     type InitListing = ();
     type BeginListing = ();
     type ListTest = ();
+    type ListFilteredOut = ();
     type EndListing = ();
 }
 
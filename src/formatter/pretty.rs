@@ -1,4 +1,9 @@
-use std::{fmt::Display, io, marker::PhantomData, time::Duration};
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    marker::PhantomData,
+    time::Duration,
+};
 
 use crate::{
     capture::OutputCapture,
@@ -52,6 +57,7 @@ impl<'t, W: io::Write, L, Extra> PrettyFormatter<'t, W, L, Extra> {
                 target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: PhantomData,
             },
         }
@@ -79,6 +85,7 @@ impl<'t, W: io::Write, L, Extra> PrettyFormatter<'t, W, L, Extra> {
                 target: self.common.target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: PhantomData,
             },
         }
@@ -96,6 +103,7 @@ impl<'t, W: io::Write, L, Extra> PrettyFormatter<'t, W, L, Extra> {
                 target: self.common.target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: PhantomData,
             },
         }
@@ -122,7 +130,11 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> PrettyFormatter<'t, W, L, Extra
 pub struct PrettyTestOutcome<'t> {
     pub name: &'t str,
     pub status: TestStatus,
+    /// Whether the test skipped itself at runtime, see
+    /// [`TestOutcome::skipped`](crate::outcome::TestOutcome::skipped).
+    pub skipped: bool,
     pub should_panic: PanicExpectation,
+    pub group: Option<usize>,
 }
 
 impl<'t, 'o, Extra> From<FmtTestOutcome<'t, 'o, Extra>> for PrettyTestOutcome<'t> {
@@ -130,7 +142,9 @@ impl<'t, 'o, Extra> From<FmtTestOutcome<'t, 'o, Extra>> for PrettyTestOutcome<'t
         Self {
             name: value.meta.name.as_ref(),
             status: value.outcome.status.clone(),
+            skipped: value.outcome.skipped(),
             should_panic: value.meta.should_panic.clone(),
+            group: value.group,
         }
     }
 }
@@ -214,15 +228,24 @@ impl<'t, Extra: 't + Sync, W: io::Write + SupportsColor + Send, L: Send> TestFor
     type TestOutcome = PrettyTestOutcome<'t>;
     fn fmt_test_outcome(&mut self, data: Self::TestOutcome) -> Result<(), Self::Error> {
         let use_color = self.use_color();
-        let target = &mut self.common.target;
+        // write the line at once, live test output may be printed at the same time
+        let target = &mut Vec::<u8>::new();
 
-        write!(target, "test {}", data.name)?;
+        write!(target, "test ")?;
+        if let Some(group) = self.common.concurrent_group(data.group) {
+            write!(target, "{group}: ")?;
+        }
+        write!(target, "{}", data.name)?;
         if let PanicExpectation::ShouldPanic | PanicExpectation::ShouldPanicWithExpected(..) =
             data.should_panic
         {
             write!(target, " - should panic")?;
         }
         write!(target, " ... ")?;
+        let ignored = match data.skipped {
+            true => "skipped",
+            false => "ignored",
+        };
         match (data.status, use_color) {
             (TestStatus::Passed, true) => write!(target, "{GREEN}ok{RESET}")?,
             (TestStatus::Passed, false) => write!(target, "ok")?,
@@ -231,25 +254,48 @@ impl<'t, Extra: 't + Sync, W: io::Write + SupportsColor + Send, L: Send> TestFor
                     reason: Some(reason),
                 },
                 true,
-            ) => write!(target, "{YELLOW}ignored, {reason}{RESET}")?,
+            ) => write!(target, "{YELLOW}{ignored}, {reason}{RESET}")?,
             (
                 TestStatus::Ignored {
                     reason: Some(reason),
                 },
                 false,
-            ) => write!(target, "ignored, {reason}")?,
+            ) => write!(target, "{ignored}, {reason}")?,
             (TestStatus::Ignored { reason: None }, true) => {
-                write!(target, "{YELLOW}ignored{RESET}")?
+                write!(target, "{YELLOW}{ignored}{RESET}")?
             }
-            (TestStatus::Ignored { reason: None }, false) => write!(target, "ignored")?,
+            (TestStatus::Ignored { reason: None }, false) => write!(target, "{ignored}")?,
             (TestStatus::TimedOut, true) => write!(target, "{RED}timed out{RESET}")?,
             (TestStatus::TimedOut, false) => write!(target, "timed out")?,
+            (TestStatus::Failed(TestFailure::UnexpectedPass), true) => {
+                write!(target, "{RED}FAILED (unexpected pass){RESET}")?
+            }
+            (TestStatus::Failed(TestFailure::UnexpectedPass), false) => {
+                write!(target, "FAILED (unexpected pass)")?
+            }
             (TestStatus::Failed(_test_failure), true) => write!(target, "{RED}FAILED{RESET}")?,
             (TestStatus::Failed(_test_failure), false) => write!(target, "FAILED")?,
+            (TestStatus::ExpectedFailure(_), true) => {
+                write!(target, "{YELLOW}expected failure{RESET}")?
+            }
+            (TestStatus::ExpectedFailure(_), false) => write!(target, "expected failure")?,
+            (TestStatus::Quarantined { failure: Some(_) }, true) => {
+                write!(target, "{YELLOW}FAILED (quarantined){RESET}")?
+            }
+            (TestStatus::Quarantined { failure: Some(_) }, false) => {
+                write!(target, "FAILED (quarantined)")?
+            }
+            (TestStatus::Quarantined { failure: None }, true) => {
+                write!(target, "{YELLOW}ok (quarantined){RESET}")?
+            }
+            (TestStatus::Quarantined { failure: None }, false) => {
+                write!(target, "ok (quarantined)")?
+            }
             (TestStatus::Other(_), true) => write!(target, "{CYAN}other{RESET}")?,
             (TestStatus::Other(_), false) => write!(target, "other")?,
         };
-        writeln!(target)
+        writeln!(target)?;
+        self.common.target.write_all(target)
     }
 
     type RunOutcomes = fto::RunOutcomes<'t>;
@@ -303,6 +349,15 @@ impl<'t, Extra: 't, W: io::Write, L> TestListFormatter<'t, Extra>
         writeln!(self.common.target, "{}: test", data.0)
     }
 
+    type ListFilteredOut = fto::TestFilteredOut<'t>;
+    fn fmt_list_filtered_out(&mut self, data: Self::ListFilteredOut) -> Result<(), Self::Error> {
+        writeln!(
+            self.common.target,
+            "{}: filtered out, {}",
+            data.name, data.reason
+        )
+    }
+
     type EndListing = fto::TestCount;
     fn fmt_end_listing(&mut self, data: Self::EndListing) -> Result<(), Self::Error> {
         match data.0 {
@@ -353,3 +408,58 @@ where
     type ListGroups = ();
     type ListGroupEnd = ();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        group::TestGroupBTreeMap,
+        ignore::DefaultIgnore,
+        test::{TestMeta, TestResult},
+        test_support::*,
+    };
+
+    #[test]
+    fn skipped_tests_are_not_reported_as_ignored() {
+        let tests = &[
+            test! {name: "ignored", ignore: "not here"},
+            test! {name: "skipped", func: || TestResult::skipped("not now")},
+        ];
+
+        let buffer = Buffer::default();
+        let report = harness(tests)
+            .with_ignore(DefaultIgnore::default())
+            .with_formatter(PrettyFormatter::default().with_target(buffer.clone()))
+            .run();
+        assert!(report.outcomes[1].1.ignored() && report.outcomes[1].1.skipped());
+        assert!(!report.outcomes[0].1.skipped());
+
+        let output = buffer.contents();
+        assert!(output.contains("test ignored ... ignored, not here\n"));
+        assert!(output.contains("test skipped ... skipped, not now\n"));
+        assert!(output.contains("0 failed; 2 ignored;"));
+    }
+
+    #[test]
+    fn concurrent_groups_prefix_their_tests() {
+        let tests = &[test! {name: "a::one"}, test! {name: "b::one"}];
+        let run = |concurrency| {
+            let buffer = Buffer::default();
+            let report = harness(tests)
+                .with_grouper(|meta: &TestMeta| meta.name[..1].to_string())
+                .with_groups(TestGroupBTreeMap::default())
+                .with_formatter(PrettyFormatter::default().with_target(buffer.clone()))
+                .run_concurrent(concurrency);
+            assert_eq!(report.outcomes.len(), 2);
+            buffer.contents()
+        };
+
+        let output = run(nonzero!(2));
+        assert!(output.contains("test a: a::one ... ok\n"));
+        assert!(output.contains("test b: b::one ... ok\n"));
+
+        let output = run(nonzero!(1));
+        assert!(output.contains("test a::one ... ok\n"));
+        assert!(output.contains("test b::one ... ok\n"));
+    }
+}
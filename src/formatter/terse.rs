@@ -11,7 +11,7 @@ use crate::{
         },
         *,
     },
-    outcome::TestStatus,
+    outcome::{TestFailure, TestStatus},
 };
 
 /// A compact formatter that keeps output minimal, while still being readable.
@@ -19,6 +19,8 @@ use crate::{
 /// Instead of printing one full status line per test, it prints a single character per test:
 /// - `.` for passed
 /// - `i` for ignored
+/// - `s` for tests that skipped themselves at runtime
+/// - `x` for expected failures
 /// - `o` for other
 ///
 /// On the first failing or timed out test, it switches to printing failure lines so we can
@@ -63,6 +65,7 @@ impl<'t, W: io::Write, L, Extra> TerseFormatter<'t, W, L, Extra> {
                 target: with_target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: self.common._label_marker,
             },
             progress: self.progress,
@@ -93,6 +96,7 @@ impl<'t, W: io::Write, L, Extra> TerseFormatter<'t, W, L, Extra> {
                 target: self.common.target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: PhantomData,
             },
             progress: self.progress,
@@ -112,6 +116,7 @@ impl<'t, W: io::Write, L, Extra> TerseFormatter<'t, W, L, Extra> {
                 target: self.common.target,
                 color_setting: self.common.color_setting,
                 tests: self.common.tests,
+                group_labels: self.common.group_labels,
                 _label_marker: PhantomData,
             },
             progress: self.progress,
@@ -132,6 +137,10 @@ impl<'t, W: io::Write + SupportsColor, L, Extra> TerseFormatter<'t, W, L, Extra>
 pub struct TerseTestOutcome<'t> {
     pub name: &'t str,
     pub status: TestStatus,
+    /// Whether the test skipped itself at runtime, see
+    /// [`TestOutcome::skipped`](crate::outcome::TestOutcome::skipped).
+    pub skipped: bool,
+    pub group: Option<usize>,
 }
 
 impl<'t, 'o, Extra> From<FmtTestOutcome<'t, 'o, Extra>> for TerseTestOutcome<'t> {
@@ -139,6 +148,8 @@ impl<'t, 'o, Extra> From<FmtTestOutcome<'t, 'o, Extra>> for TerseTestOutcome<'t>
         Self {
             name: value.meta.name.as_ref(),
             status: value.outcome.status.clone(),
+            skipped: value.outcome.skipped(),
+            group: value.group,
         }
     }
 }
@@ -170,7 +181,11 @@ impl<'t, W: io::Write + Send + SupportsColor, L: Send, Extra: 't + Sync> TestFor
         let target = &mut self.common.target;
         let write_res = match data.status {
             TestStatus::Passed => write!(target, "{green}.{reset}"),
+            TestStatus::Ignored { .. } if data.skipped => write!(target, "{yellow}s{reset}"),
             TestStatus::Ignored { .. } => write!(target, "{yellow}i{reset}"),
+            TestStatus::ExpectedFailure(..) => write!(target, "{yellow}x{reset}"),
+            TestStatus::Quarantined { failure: Some(_) } => write!(target, "{yellow}q{reset}"),
+            TestStatus::Quarantined { failure: None } => write!(target, "{yellow}Q{reset}"),
             TestStatus::Other(..) => write!(target, "{cyan}o{reset}"),
             TestStatus::Failed(..) | TestStatus::TimedOut => {
                 if self.last_ok {
@@ -181,14 +196,26 @@ impl<'t, W: io::Write + Send + SupportsColor, L: Send, Extra: 't + Sync> TestFor
                         self.common.tests.len()
                     )?;
                 }
-                writeln!(self.common.target, "{} --- {red}FAILED{reset}", data.name)
+                if let Some(group) = data.group.and_then(|i| self.common.group_labels.get(&i)) {
+                    write!(self.common.target, "{group}: ")?;
+                }
+                match data.status {
+                    TestStatus::Failed(TestFailure::UnexpectedPass) => writeln!(
+                        self.common.target,
+                        "{} --- {red}FAILED (unexpected pass){reset}",
+                        data.name
+                    ),
+                    _ => writeln!(self.common.target, "{} --- {red}FAILED{reset}", data.name),
+                }
             }
         };
 
         match data.status {
-            TestStatus::Passed | TestStatus::Ignored { .. } | TestStatus::Other(..) => {
-                self.last_ok = true
-            }
+            TestStatus::Passed
+            | TestStatus::Ignored { .. }
+            | TestStatus::ExpectedFailure(..)
+            | TestStatus::Quarantined { .. }
+            | TestStatus::Other(..) => self.last_ok = true,
             TestStatus::TimedOut | TestStatus::Failed(..) => self.last_ok = false,
         }
 
@@ -248,6 +275,15 @@ impl<'t, W: io::Write, L, Extra: 't> TestListFormatter<'t, Extra>
         writeln!(self.common.target, "{}: test", data.0)
     }
 
+    type ListFilteredOut = fto::TestFilteredOut<'t>;
+    fn fmt_list_filtered_out(&mut self, data: Self::ListFilteredOut) -> Result<(), Self::Error> {
+        writeln!(
+            self.common.target,
+            "{}: filtered out, {}",
+            data.name, data.reason
+        )
+    }
+
     type InitListing = ();
     type BeginListing = ();
     type EndListing = ();
@@ -264,3 +300,58 @@ where
     type ListGroupStart = ();
     type ListGroupEnd = ();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        group::TestGroupBTreeMap,
+        ignore::DefaultIgnore,
+        test::{TestMeta, TestResult},
+        test_support::*,
+    };
+
+    #[test]
+    fn skipped_tests_are_not_reported_as_ignored() {
+        let tests = &[
+            test! {name: "ignored", ignore: true},
+            test! {name: "skipped", func: || TestResult::skipped("not now")},
+        ];
+
+        let buffer = Buffer::default();
+        let report = harness(tests)
+            .with_ignore(DefaultIgnore::default())
+            .with_formatter(TerseFormatter::default().with_target(buffer.clone()))
+            .run();
+        assert!(report.outcomes[1].1.ignored() && report.outcomes[1].1.skipped());
+        assert!(!report.outcomes[0].1.skipped());
+
+        assert!(buffer.contents().contains("is\n"));
+    }
+
+    #[test]
+    fn concurrent_groups_prefix_their_failures() {
+        let tests = &[
+            test! {name: "a::fail", func: || Err::<(), _>("failed")},
+            test! {name: "b::fail", func: || Err::<(), _>("failed")},
+        ];
+        let run = |concurrency| {
+            let buffer = Buffer::default();
+            let report = harness(tests)
+                .with_grouper(|meta: &TestMeta| meta.name[..1].to_string())
+                .with_groups(TestGroupBTreeMap::default())
+                .with_formatter(TerseFormatter::default().with_target(buffer.clone()))
+                .run_concurrent(concurrency);
+            assert_eq!(report.outcomes.len(), 2);
+            buffer.contents()
+        };
+
+        let output = run(nonzero!(2));
+        assert!(output.contains("a: a::fail --- FAILED\n"));
+        assert!(output.contains("b: b::fail --- FAILED\n"));
+
+        let output = run(nonzero!(1));
+        assert!(output.contains("\na::fail --- FAILED\n"));
+        assert!(output.contains("\nb::fail --- FAILED\n"));
+    }
+}
@@ -1,10 +1,24 @@
-use std::{marker::PhantomData, ops::ControlFlow, sync::Arc, time::Instant};
+use std::{
+    iter,
+    marker::PhantomData,
+    num::NonZeroUsize,
+    ops::ControlFlow,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::Scope,
+    time::Instant,
+};
+
+use crossbeam_channel::Sender;
 
 use crate::{
-    GroupedTestReport, TestListReport,
+    GroupedTestOutcomes, GroupedTestReport, TestListReport,
+    capture::{LiveOutputGuard, TEST_OUTPUT_CAPTURE, TEST_PANIC_INFO},
     filter::{FilteredTests, TestFilter},
     formatter::*,
-    group::{TestGroupRunner, TestGrouper, TestGroups},
+    group::{TestGroupOutcomes, TestGroupRunner, TestGrouper, TestGroups},
     harness::FmtErrors,
     ignore::{IgnoreStatus, TestIgnore},
     outcome::TestStatus,
@@ -69,6 +83,9 @@ pub struct GroupedTestHarness<
     pub(crate) panic_handler: PanicHandler,
     pub(crate) runner: Runner,
     pub(crate) formatter: Formatter,
+    pub(crate) no_capture: bool,
+    pub(crate) output_limit: Option<usize>,
+    pub(crate) explain: bool,
 }
 
 impl<
@@ -105,9 +122,10 @@ impl<
     /// This runs the grouped test pipeline:
     /// - filters tests
     /// - assigns tests to groups via the configured grouper
-    /// - executes groups via the group runner
+    /// - executes groups via the group runner, one after another
     /// - executes tests inside each group through the runner
-    /// - captures output and panics per test
+    /// - captures output and panics per test, unless
+    ///   [`with_no_capture`](Self::with_no_capture) is set
     /// - forwards group and test events to the grouped formatter
     ///
     /// The harness is consumed by this call.
@@ -116,7 +134,48 @@ impl<
     ///
     /// Formatting errors are collected and included in the report instead of
     /// aborting the run early.
-    pub fn run(mut self) -> GroupedTestReport<'t, GroupKey, GroupCtx, Formatter::Error> {
+    ///
+    /// Use [`run_concurrent`](Self::run_concurrent) to run independent groups at the same time.
+    pub fn run(self) -> GroupedTestReport<'t, GroupKey, GroupCtx, Formatter::Error> {
+        self.run_with(
+            NonZeroUsize::MIN,
+            |scope, mut grouper, groups, group_run| {
+                groups
+                    .into_groups()
+                    .enumerate()
+                    .map_until_inclusive(|(index, (key, tests))| {
+                        let ctx = grouper.group_ctx(&key);
+                        group_run.run(scope, index, key, tests, ctx)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// The grouped test pipeline, with `run_groups` executing the groups.
+    fn run_with<R>(
+        mut self,
+        group_concurrency: NonZeroUsize,
+        run_groups: R,
+    ) -> GroupedTestReport<'t, GroupKey, GroupCtx, Formatter::Error>
+    where
+        R: for<'s> FnOnce(
+            &'s Scope<'s, 't>,
+            Grouper,
+            Groups,
+            GroupRun<
+                't,
+                Extra,
+                GroupKey,
+                GroupCtx,
+                Ignore,
+                GroupRunner,
+                PanicHandler,
+                Runner,
+                Formatter,
+            >,
+        ) -> GroupedTestOutcomes<'t, GroupKey, GroupCtx>,
+    {
         let now = Instant::now();
 
         let mut formatter = self.formatter;
@@ -165,114 +224,19 @@ impl<
                 (formatter, fmt_errors)
             });
 
-            let ignore = Arc::new(self.ignore);
-            let panic_handler = Arc::new(self.panic_handler);
-            let runner = Arc::new(self.runner);
-
-            let group_runs = self
-                .groups
-                .into_groups()
-                .map_until_inclusive(|(key, tests)| {
-                    let now = Instant::now();
-
-                    let ignore = Arc::clone(&ignore);
-                    let panic_handler = Arc::clone(&panic_handler);
-                    let runner = Arc::clone(&runner);
-                    let ftx = ftx.clone();
-                    let ctx = self.grouper.group_ctx(&key);
-
-                    let _ = ftx.send(FmtGroupedTestData::Start(
-                        FmtGroupStart {
-                            tests: tests.len(),
-                            worker_count: runner.worker_count(tests.len()),
-                            key: &key,
-                            ctx: ctx.as_ref(),
-                        }
-                        .into(),
-                    ));
-
-                    let outcomes = self.group_runner.run_group(
-                        move || {
-                            let test_runs = tests.into_iter().map(|test| {
-                                let meta = &test.meta;
-                                let ignore = Arc::clone(&ignore);
-                                let panic_handler = Arc::clone(&panic_handler);
-                                let ftx = ftx.clone();
-
-                                (
-                                    move || {
-                                        let reason = match ignore.ignore(meta) {
-                                            IgnoreStatus::Run => {
-                                                let _ = ftx.send(FmtGroupedTestData::Test(
-                                                    FmtTestData::Start(
-                                                        FmtTestStart { meta }.into(),
-                                                    ),
-                                                ));
-                                                return panic_handler.handle(|| test.call(), meta);
-                                            }
-                                            IgnoreStatus::Ignore => None,
-                                            IgnoreStatus::IgnoreWithReason(reason) => Some(reason),
-                                        };
-                                        let _ = ftx.send(FmtGroupedTestData::Test(
-                                            FmtTestData::Ignored(
-                                                FmtTestIgnored {
-                                                    meta,
-                                                    reason: reason.as_ref(),
-                                                }
-                                                .into(),
-                                            ),
-                                        ));
-                                        TestStatus::Ignored { reason }
-                                    },
-                                    meta,
-                                )
-                            });
-
-                            runner
-                                .run(test_runs, scope)
-                                .inspect(|(meta, outcome)| {
-                                    let _ =
-                                        ftx.send(FmtGroupedTestData::Test(FmtTestData::Outcome(
-                                            FmtTestOutcome {
-                                                meta: *meta,
-                                                outcome,
-                                            }
-                                            .into(),
-                                        )));
-                                })
-                                .map(|(meta, outcome)| (meta.name.as_ref(), outcome))
-                                .collect()
-                        },
-                        &key,
-                        ctx.as_ref(),
-                    );
-
-                    match outcomes {
-                        ControlFlow::Continue(out) => {
-                            ControlFlow::Continue((out, now.elapsed(), key, ctx))
-                        }
-                        ControlFlow::Break(out) => {
-                            ControlFlow::Break((out, now.elapsed(), key, ctx))
-                        }
-                    }
-                });
+            let group_run = GroupRun {
+                ignore: Arc::new(self.ignore),
+                panic_handler: Arc::new(self.panic_handler),
+                group_runner: self.group_runner,
+                runner: self.runner,
+                ftx,
+                no_capture: self.no_capture,
+                output_limit: self.output_limit,
+                group_concurrency,
+                _types: PhantomData,
+            };
+            let grouped_outcomes = run_groups(scope, self.grouper, self.groups, group_run);
 
-            let grouped_outcomes = group_runs
-                .inspect(|(outcomes, duration, key, ctx)| {
-                    let _ = ftx.send(FmtGroupedTestData::Outcome(
-                        FmtGroupOutcomes {
-                            outcomes,
-                            duration: *duration,
-                            key,
-                            ctx: ctx.as_ref(),
-                        }
-                        .into(),
-                    ));
-                })
-                .map(|(outcomes, _, key, ctx)| (key, outcomes, ctx))
-                .collect();
-
-            drop(ftx);
             let (formatter, fmt_errors) = fmt_thread
                 .join()
                 .expect("format thread should join without issues");
@@ -297,6 +261,291 @@ impl<
     }
 }
 
+impl<
+    't,
+    Extra: Sync,
+    GroupKey: Send + 't,
+    GroupCtx: Send + 't,
+    Filter: TestFilter<Extra>,
+    Grouper: TestGrouper<Extra, GroupKey, GroupCtx> + Send + 't,
+    Groups: TestGroups<'t, Extra, GroupKey>,
+    Ignore: TestIgnore<Extra> + Send + Sync + 't,
+    GroupRunner: TestGroupRunner<'t, Extra, GroupKey, GroupCtx> + Send + Sync + 't,
+    PanicHandler: TestPanicHandler<Extra> + Send + Sync + 't,
+    Runner: TestRunner<'t, Extra> + Send + Sync + 't,
+    Formatter: GroupedTestFormatter<'t, Extra, GroupKey, GroupCtx> + 't,
+>
+    GroupedTestHarness<
+        't,
+        Extra,
+        GroupKey,
+        GroupCtx,
+        Filter,
+        Grouper,
+        Groups,
+        Ignore,
+        GroupRunner,
+        PanicHandler,
+        Runner,
+        Formatter,
+    >
+{
+    /// Execute the grouped test harness like [`run`](Self::run), with up to
+    /// `group_concurrency` groups at the same time.
+    ///
+    /// Each group still runs through [`TestGroupRunner::run_group`] with its own context, and the
+    /// [runner](Self::with_runner) runs the tests inside each group as usual.
+    /// The number of threads running tests is therefore up to `group_concurrency` times the
+    /// worker count of the runner.
+    ///
+    /// Once a group runner breaks, no further groups are started, but groups that already run
+    /// finish.
+    /// The outcomes in the report keep the order of the groups.
+    ///
+    /// Additional groups run on scoped threads, which is why the group keys, contexts and
+    /// strategies have to be sendable here.
+    ///
+    /// The formatter receives the events of concurrent groups interleaved, test events carry the
+    /// [index](FmtGroupStart::index) of their group.
+    pub fn run_concurrent(
+        self,
+        group_concurrency: NonZeroUsize,
+    ) -> GroupedTestReport<'t, GroupKey, GroupCtx, Formatter::Error> {
+        self.run_with(
+            group_concurrency,
+            |scope, grouper, groups, mut group_run| {
+                let groups: Vec<_> = groups
+                    .into_groups()
+                    .map(|(key, tests)| (key, tests.collect::<Vec<_>>()))
+                    .collect();
+                let group_concurrency = group_concurrency
+                    .min(NonZeroUsize::new(groups.len()).unwrap_or(NonZeroUsize::MIN));
+                group_run.group_concurrency = group_concurrency;
+                // the grouper is behind the same lock, contexts are created in group order
+                let pending = Arc::new(Mutex::new((grouper, groups.into_iter().enumerate())));
+                let stop = Arc::new(AtomicBool::new(false));
+                let group_run = Arc::new(group_run);
+
+                // every worker takes the next pending group until none are left or a group breaks
+                let worker = || {
+                    let pending = Arc::clone(&pending);
+                    let stop = Arc::clone(&stop);
+                    let group_run = Arc::clone(&group_run);
+
+                    move || {
+                        let next_group = || {
+                            if stop.load(Ordering::Relaxed) {
+                                return None;
+                            }
+                            let mut pending = pending.lock().expect("pending groups lock poisoned");
+                            let (grouper, groups) = &mut *pending;
+                            groups.next().map(|(index, (key, tests))| {
+                                let ctx = grouper.group_ctx(&key);
+                                (index, key, tests, ctx)
+                            })
+                        };
+
+                        iter::from_fn(next_group)
+                            .map_until_inclusive(|(index, key, tests, ctx)| {
+                                let group =
+                                    group_run.run(scope, index, key, tests.into_iter(), ctx);
+                                if group.is_break() {
+                                    stop.store(true, Ordering::Relaxed);
+                                }
+                                match group {
+                                    ControlFlow::Continue(group) => {
+                                        ControlFlow::Continue((index, group))
+                                    }
+                                    ControlFlow::Break(group) => ControlFlow::Break((index, group)),
+                                }
+                            })
+                            .collect::<Vec<_>>()
+                    }
+                };
+
+                // the calling thread is always one of the workers
+                let workers: Vec<_> = (1..group_concurrency.get())
+                    .map(|_| scope.spawn(worker()))
+                    .collect();
+                let mut grouped_outcomes = worker()();
+                for worker in workers {
+                    grouped_outcomes.extend(worker.join().expect("group worker should not panic"));
+                }
+                grouped_outcomes.sort_by_key(|(index, _)| *index);
+                grouped_outcomes
+                    .into_iter()
+                    .map(|(_, group)| group)
+                    .collect()
+            },
+        )
+    }
+}
+
+type GroupOutcome<'t, GroupKey, GroupCtx> = (GroupKey, TestGroupOutcomes<'t>, Option<GroupCtx>);
+
+type FmtSender<'t, Extra, GroupKey, GroupCtx, Formatter> = Sender<
+    FmtGroupedTestData<
+        <Formatter as TestFormatter<'t, Extra>>::TestIgnored,
+        <Formatter as TestFormatter<'t, Extra>>::TestStart,
+        <Formatter as TestFormatter<'t, Extra>>::TestOutcome,
+        <Formatter as GroupedTestFormatter<'t, Extra, GroupKey, GroupCtx>>::GroupStart,
+        <Formatter as GroupedTestFormatter<'t, Extra, GroupKey, GroupCtx>>::GroupOutcomes,
+    >,
+>;
+
+/// Everything needed to run a single group, shared by [`GroupedTestHarness::run`] and
+/// [`GroupedTestHarness::run_concurrent`].
+struct GroupRun<'t, Extra, GroupKey, GroupCtx, Ignore, GroupRunner, PanicHandler, Runner, Formatter>
+where
+    Extra: 't,
+    GroupKey: 't,
+    GroupCtx: 't,
+    Formatter: GroupedTestFormatter<'t, Extra, GroupKey, GroupCtx>,
+{
+    ignore: Arc<Ignore>,
+    panic_handler: Arc<PanicHandler>,
+    group_runner: GroupRunner,
+    runner: Runner,
+    ftx: FmtSender<'t, Extra, GroupKey, GroupCtx, Formatter>,
+    no_capture: bool,
+    output_limit: Option<usize>,
+    group_concurrency: NonZeroUsize,
+    _types: PhantomData<fn() -> (&'t Extra, GroupKey, GroupCtx)>,
+}
+
+impl<'t, Extra, GroupKey, GroupCtx, Ignore, GroupRunner, PanicHandler, Runner, Formatter>
+    GroupRun<'t, Extra, GroupKey, GroupCtx, Ignore, GroupRunner, PanicHandler, Runner, Formatter>
+where
+    Extra: Sync + 't,
+    GroupKey: 't,
+    GroupCtx: 't,
+    Ignore: TestIgnore<Extra> + Send + Sync + 't,
+    GroupRunner: TestGroupRunner<'t, Extra, GroupKey, GroupCtx>,
+    PanicHandler: TestPanicHandler<Extra> + Send + Sync + 't,
+    Runner: TestRunner<'t, Extra>,
+    Formatter: GroupedTestFormatter<'t, Extra, GroupKey, GroupCtx> + 't,
+{
+    /// Run the group at `index` and report it to the formatter.
+    fn run<'s>(
+        &self,
+        scope: &'s Scope<'s, 't>,
+        index: usize,
+        key: GroupKey,
+        tests: impl ExactSizeIterator<Item = &'t Test<Extra>>,
+        ctx: Option<GroupCtx>,
+    ) -> ControlFlow<GroupOutcome<'t, GroupKey, GroupCtx>, GroupOutcome<'t, GroupKey, GroupCtx>>
+    {
+        let now = Instant::now();
+
+        let ignore = Arc::clone(&self.ignore);
+        let panic_handler = Arc::clone(&self.panic_handler);
+        let ftx = self.ftx.clone();
+        let output_limit = self.output_limit;
+        let worker_count = self.runner.worker_count(tests.len());
+        // prefix live output with the test name only if tests may run at the same time
+        let live_output = self
+            .no_capture
+            .then_some(worker_count.get() > 1 || self.group_concurrency.get() > 1);
+
+        let _ = self.ftx.send(FmtGroupedTestData::Start(
+            FmtGroupStart {
+                tests: tests.len(),
+                worker_count,
+                index,
+                group_concurrency: self.group_concurrency,
+                key: &key,
+                ctx: ctx.as_ref(),
+            }
+            .into(),
+        ));
+
+        let outcomes = self.group_runner.run_group(
+            move || {
+                let test_runs = tests.map(|test| {
+                    let meta = &test.meta;
+                    let ignore = Arc::clone(&ignore);
+                    let panic_handler = Arc::clone(&panic_handler);
+                    let ftx = ftx.clone();
+
+                    (
+                        move || {
+                            let reason = match ignore.ignore(meta) {
+                                IgnoreStatus::Run => {
+                                    let _ = ftx.send(FmtGroupedTestData::Test(FmtTestData::Start(
+                                        FmtTestStart {
+                                            meta,
+                                            group: Some(index),
+                                        }
+                                        .into(),
+                                    )));
+                                    TEST_OUTPUT_CAPTURE.with_borrow_mut(|capture| {
+                                        capture.start_test(output_limit)
+                                    });
+                                    TEST_PANIC_INFO.set(None);
+                                    let _live_output = live_output.map(|prefixed| {
+                                        LiveOutputGuard::start(
+                                            prefixed.then_some(meta.name.as_ref()),
+                                        )
+                                    });
+                                    return panic_handler.handle(|| test.call(), meta);
+                                }
+                                IgnoreStatus::Ignore => None,
+                                IgnoreStatus::IgnoreWithReason(reason) => Some(reason),
+                            };
+                            let _ = ftx.send(FmtGroupedTestData::Test(FmtTestData::Ignored(
+                                FmtTestIgnored {
+                                    meta,
+                                    reason: reason.as_ref(),
+                                    group: Some(index),
+                                }
+                                .into(),
+                            )));
+                            TestStatus::Ignored { reason }
+                        },
+                        meta,
+                    )
+                });
+
+                self.runner
+                    .run(test_runs, scope)
+                    .inspect(|(meta, outcome)| {
+                        let _ = ftx.send(FmtGroupedTestData::Test(FmtTestData::Outcome(
+                            FmtTestOutcome {
+                                meta: *meta,
+                                outcome,
+                                group: Some(index),
+                            }
+                            .into(),
+                        )));
+                    })
+                    .map(|(meta, outcome)| (meta.name.as_ref(), outcome))
+                    .collect()
+            },
+            &key,
+            ctx.as_ref(),
+        );
+
+        let duration = now.elapsed();
+        let (ControlFlow::Continue(group_outcomes) | ControlFlow::Break(group_outcomes)) =
+            &outcomes;
+        let _ = self.ftx.send(FmtGroupedTestData::Outcome(
+            FmtGroupOutcomes {
+                outcomes: group_outcomes,
+                duration,
+                index,
+                key: &key,
+                ctx: ctx.as_ref(),
+            }
+            .into(),
+        ));
+
+        match outcomes {
+            ControlFlow::Continue(outcomes) => ControlFlow::Continue((key, outcomes, ctx)),
+            ControlFlow::Break(outcomes) => ControlFlow::Break((key, outcomes, ctx)),
+        }
+    }
+}
+
 impl<
     't,
     Extra,
@@ -406,6 +655,18 @@ impl<
             );
         }
 
+        if self.explain {
+            for exclusion in self.filter.explain(self.tests) {
+                fmt_errors.push_on_error(
+                    FmtListFilteredOut {
+                        meta: &exclusion.test.meta,
+                        reason: &exclusion.reason,
+                    }
+                    .fmt(|data| formatter.fmt_list_filtered_out(data)),
+                );
+            }
+        }
+
         fmt_errors.push_on_error(
             FmtEndListing {
                 active: active_count,
@@ -480,6 +741,9 @@ impl<
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -516,6 +780,9 @@ impl<
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -552,6 +819,9 @@ impl<
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -589,6 +859,9 @@ impl<
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -625,6 +898,9 @@ impl<
             panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -661,6 +937,9 @@ impl<
             panic_handler: self.panic_handler,
             runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -697,6 +976,165 @@ impl<
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
+        }
+    }
+
+    /// Print test output directly instead of capturing it.
+    ///
+    /// This behaves like [`TestHarness::with_no_capture`](super::TestHarness::with_no_capture).
+    /// Lines are prefixed with the test name if the runner uses more than one worker for a group.
+    pub fn with_no_capture(self, no_capture: bool) -> Self {
+        Self { no_capture, ..self }
+    }
+
+    /// Limit the captured output of every test to `limit` bytes.
+    ///
+    /// This behaves like [`TestHarness::with_output_limit`](super::TestHarness::with_output_limit).
+    pub fn with_output_limit(self, output_limit: Option<usize>) -> Self {
+        Self {
+            output_limit,
+            ..self
+        }
+    }
+
+    /// Explain why tests are filtered out when listing.
+    ///
+    /// This behaves like [`TestHarness::with_explain`](super::TestHarness::with_explain).
+    pub fn with_explain(self, explain: bool) -> Self {
+        Self { explain, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc, sync::atomic::AtomicUsize, thread, time::Duration};
+
+    use super::*;
+    use crate::{
+        group::{TestGroupBTreeMap, TestGroupOutcomes},
+        test::TestMeta,
+        test_support::*,
+    };
+
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Wait until the tests of both groups started, this only happens if the groups overlap.
+    fn meet() {
+        STARTED.fetch_add(1, Ordering::SeqCst);
+        let start = Instant::now();
+        while STARTED.load(Ordering::SeqCst) < 2 {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "groups did not overlap"
+            );
+            thread::yield_now();
         }
     }
+
+    struct BreakAfterFirst;
+
+    impl<'t> TestGroupRunner<'t, (), String, ()> for BreakAfterFirst {
+        fn run_group<F>(
+            &self,
+            f: F,
+            _: &String,
+            _: Option<&()>,
+        ) -> ControlFlow<TestGroupOutcomes<'t>, TestGroupOutcomes<'t>>
+        where
+            F: FnOnce() -> TestGroupOutcomes<'t>,
+        {
+            ControlFlow::Break(f())
+        }
+    }
+
+    fn by_module(meta: &TestMeta) -> String {
+        meta.name.split("::").next().unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn groups_run_concurrently() {
+        let tests = &[
+            test! {name: "a::meet", func: meet},
+            test! {name: "b::meet", func: meet},
+            test! {name: "c::pass"},
+        ];
+
+        let report = harness(tests)
+            .with_grouper(by_module)
+            .with_groups(TestGroupBTreeMap::default())
+            .run_concurrent(nonzero!(2));
+        let groups: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(key, outcomes, _)| (key.as_str(), outcomes.len(), outcomes[0].1.passed()))
+            .collect();
+        assert_eq!(groups, [("a", 1, true), ("b", 1, true), ("c", 1, true)]);
+
+        let report = harness(tests)
+            .with_grouper(by_module)
+            .with_groups(TestGroupBTreeMap::default())
+            .with_group_runner(BreakAfterFirst)
+            .run();
+        assert_eq!(report.outcomes.len(), 1);
+        assert_eq!(report.outcomes[0].0, "a");
+    }
+
+    /// Hands out a context that is neither [`Send`] nor [`Sync`].
+    struct LocalCtx;
+
+    impl TestGrouper<(), String, Rc<()>> for LocalCtx {
+        fn group(&mut self, meta: &TestMeta) -> String {
+            by_module(meta)
+        }
+
+        fn group_ctx(&mut self, _: &String) -> Option<Rc<()>> {
+            Some(Rc::new(()))
+        }
+    }
+
+    /// Checks that groups never overlap, without being [`Sync`].
+    #[derive(Default)]
+    struct OneAtATime(Cell<bool>);
+
+    impl<'t> TestGroupRunner<'t, (), String, Rc<()>> for OneAtATime {
+        fn run_group<F>(
+            &self,
+            f: F,
+            _: &String,
+            ctx: Option<&Rc<()>>,
+        ) -> ControlFlow<TestGroupOutcomes<'t>, TestGroupOutcomes<'t>>
+        where
+            F: FnOnce() -> TestGroupOutcomes<'t>,
+        {
+            assert!(ctx.is_some());
+            assert!(!self.0.replace(true), "groups overlap");
+            let outcomes = f();
+            self.0.set(false);
+            ControlFlow::Continue(outcomes)
+        }
+    }
+
+    #[test]
+    fn sequential_runs_accept_local_contexts() {
+        let tests = &[
+            test! {name: "a::one"},
+            test! {name: "a::two"},
+            test! {name: "b::one"},
+        ];
+
+        let report = harness(tests)
+            .with_grouper(LocalCtx)
+            .with_groups(TestGroupBTreeMap::default())
+            .with_group_runner(OneAtATime::default())
+            .run();
+        let groups: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(key, outcomes, ctx)| (key.as_str(), outcomes.len(), ctx.is_some()))
+            .collect();
+        assert_eq!(groups, [("a", 2, true), ("b", 1, true)]);
+    }
 }
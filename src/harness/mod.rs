@@ -77,6 +77,9 @@ pub fn harness<'t, Extra>(
         panic_handler: DefaultPanicHandler,
         runner: DefaultRunner::default(),
         formatter: PrettyFormatter::default(),
+        no_capture: false,
+        output_limit: None,
+        explain: false,
     }
 }
 
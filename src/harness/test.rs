@@ -2,6 +2,7 @@ use std::{marker::PhantomData, sync::Arc, time::Instant};
 
 use crate::{
     GroupedTestHarness, TestListReport, TestReport,
+    capture::{LiveOutputGuard, TEST_OUTPUT_CAPTURE, TEST_PANIC_INFO},
     filter::{FilteredTests, TestFilter},
     formatter::*,
     group::{SimpleGroupRunner, TestGroupHashMap, TestGrouper},
@@ -58,6 +59,9 @@ pub struct TestHarness<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatte
     pub(crate) panic_handler: PanicHandler,
     pub(crate) runner: Runner,
     pub(crate) formatter: Formatter,
+    pub(crate) no_capture: bool,
+    pub(crate) output_limit: Option<usize>,
+    pub(crate) explain: bool,
 }
 
 impl<
@@ -76,7 +80,7 @@ impl<
     /// - filters tests
     /// - applies ignore rules
     /// - executes tests through the runner
-    /// - captures output and panics
+    /// - captures output and panics, unless [`with_no_capture`](Self::with_no_capture) is set
     /// - forwards events to the formatter
     ///
     /// The harness is consumed by this call. After running, the result is returned
@@ -107,6 +111,11 @@ impl<
 
         let ignore = Arc::new(self.ignore);
         let panic_handler = Arc::new(self.panic_handler);
+        // prefix live output with the test name only if tests may run at the same time
+        let live_output = self
+            .no_capture
+            .then(|| self.runner.worker_count(tests.len()).get() > 1);
+        let output_limit = self.output_limit;
 
         let (outcomes, mut formatter, mut fmt_errors) = std::thread::scope(move |scope| {
             let (ftx, frx) =
@@ -138,7 +147,15 @@ impl<
                     move || {
                         let reason = match ignore.ignore(meta) {
                             IgnoreStatus::Run => {
-                                let _ = ftx.send(FmtTestData::Start(FmtTestStart { meta }.into()));
+                                let _ = ftx.send(FmtTestData::Start(
+                                    FmtTestStart { meta, group: None }.into(),
+                                ));
+                                TEST_OUTPUT_CAPTURE
+                                    .with_borrow_mut(|capture| capture.start_test(output_limit));
+                                TEST_PANIC_INFO.set(None);
+                                let _live_output = live_output.map(|prefixed| {
+                                    LiveOutputGuard::start(prefixed.then_some(meta.name.as_ref()))
+                                });
                                 return panic_handler.handle(|| test.call(), meta);
                             }
                             IgnoreStatus::Ignore => None,
@@ -149,6 +166,7 @@ impl<
                             FmtTestIgnored {
                                 meta,
                                 reason: reason.as_ref(),
+                                group: None,
                             }
                             .into(),
                         ));
@@ -167,6 +185,7 @@ impl<
                         FmtTestOutcome {
                             meta: *meta,
                             outcome,
+                            group: None,
                         }
                         .into(),
                     ));
@@ -255,6 +274,18 @@ impl<
             );
         }
 
+        if self.explain {
+            for exclusion in self.filter.explain(self.tests) {
+                fmt_errors.push_on_error(
+                    FmtListFilteredOut {
+                        meta: &exclusion.test.meta,
+                        reason: &exclusion.reason,
+                    }
+                    .fmt(|data| formatter.fmt_list_filtered_out(data)),
+                );
+            }
+        }
+
         fmt_errors.push_on_error(
             FmtEndListing {
                 active: active_count,
@@ -288,6 +319,9 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -308,6 +342,9 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -327,6 +364,9 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -348,6 +388,9 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler: self.panic_handler,
             runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 
@@ -368,9 +411,49 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
+        }
+    }
+
+    /// Print test output directly instead of capturing it.
+    ///
+    /// This is the equivalent of `--no-capture` of the built in Rust test harness.
+    /// Output written through Kitest's capture aware macros, including panic messages of the
+    /// default panic hook, goes straight to the process stdout and stderr while the test runs.
+    /// If the runner uses more than one worker, every line is prefixed with the test name to keep
+    /// output of parallel tests apart.
+    ///
+    /// Output is forwarded line by line, so it does not interleave with complete lines written by
+    /// the formatter.
+    /// The outcomes of the tests contain no output.
+    pub fn with_no_capture(self, no_capture: bool) -> Self {
+        Self { no_capture, ..self }
+    }
+
+    /// Limit the captured output of every test to `limit` bytes.
+    ///
+    /// Tests writing more keep the start and the end of their output, the part in between is
+    /// replaced by a marker, see [`OutputCapture::set_limit`](crate::capture::OutputCapture::set_limit).
+    /// Pass `None` to capture everything, which is the default.
+    pub fn with_output_limit(self, output_limit: Option<usize>) -> Self {
+        Self {
+            output_limit,
+            ..self
         }
     }
 
+    /// Explain why tests are filtered out when listing.
+    ///
+    /// This is the equivalent of `--list --explain`.
+    /// [`list`](Self::list) asks the filter for the reason of every filtered out test via
+    /// [`TestFilter::explain`] and reports it to the formatter after the listed tests.
+    /// Running tests is not affected.
+    pub fn with_explain(self, explain: bool) -> Self {
+        Self { explain, ..self }
+    }
+
     /// Enable grouping and promote this harness into a [`GroupedTestHarness`].
     ///
     /// Calling this method switches the execution model from individual tests to
@@ -408,6 +491,9 @@ impl<'t, Extra, Filter, Ignore, PanicHandler, Runner, Formatter>
             panic_handler: self.panic_handler,
             runner: self.runner,
             formatter: self.formatter,
+            no_capture: self.no_capture,
+            output_limit: self.output_limit,
+            explain: self.explain,
         }
     }
 }
@@ -256,9 +256,13 @@
 //! Kitest focuses only on building and running test harnesses.
 
 pub mod capture;
+pub mod check;
+pub mod executor;
 pub mod formatter;
 pub mod outcome;
+pub mod pattern;
 pub mod test;
+pub mod thread;
 
 mod strategy;
 pub use strategy::*;
@@ -19,7 +19,13 @@ use std::{
     time::Duration,
 };
 
-use crate::{Whatever, capture::OutputCapture, test::TestResult};
+use crate::{
+    Whatever,
+    capture::{OutputCapture, PanicLocation},
+    check::{CheckFailure, CheckFailures},
+    panic::PanicRule,
+    test::{Skipped, TestResult},
+};
 
 /// The outcome of a single test execution.
 ///
@@ -52,6 +58,16 @@ pub struct TestOutcome {
     pub attachments: TestOutcomeAttachments,
 }
 
+impl TestOutcome {
+    /// Returns `true` if the test skipped itself at runtime.
+    ///
+    /// The status of a skipped test is [`TestStatus::Ignored`], the runner attaches the
+    /// [`Skipped`] to tell it apart from tests ignored before they ran.
+    pub fn skipped(&self) -> bool {
+        self.attachments.get::<Skipped>().is_some()
+    }
+}
+
 impl Deref for TestOutcome {
     type Target = TestStatus;
 
@@ -84,6 +100,9 @@ pub enum TestStatus {
     /// A reason may be provided.
     /// This does not have to originate from the test metadata and may be decided dynamically by
     /// the [`TestIgnore`](super::ignore::TestIgnore) strategy.
+    ///
+    /// Tests may also skip themselves at runtime, see [`Skipped`].
+    /// Their outcome carries the [`Skipped`] as attachment, see [`TestOutcome::skipped`].
     Ignored {
         /// Optional reason why the test was ignored.
         reason: Option<Cow<'static, str>>,
@@ -94,6 +113,29 @@ pub enum TestStatus {
     /// This variant carries more detailed failure information.
     Failed(TestFailure),
 
+    /// The test failed, as it was expected to.
+    ///
+    /// This is produced for tests marked as expected failures, see
+    /// [`XFailPanicHandler`](super::panic::XFailPanicHandler).
+    /// The contained failure is the one the test would have failed with otherwise.
+    ///
+    /// An expected failure is treated as a good outcome.
+    /// If such a test passes instead, it fails with [`TestFailure::UnexpectedPass`].
+    ExpectedFailure(TestFailure),
+
+    /// The test is quarantined, its result does not count.
+    ///
+    /// This is produced for tests on a quarantine list, see
+    /// [`QuarantinePanicHandler`](super::panic::QuarantinePanicHandler).
+    /// Quarantined tests still run, so we notice once they are fixed, but they are treated as a
+    /// good outcome either way.
+    Quarantined {
+        /// The failure of the test, `None` if the quarantined test passed.
+        ///
+        /// A quarantined test that passes should be removed from the quarantine.
+        failure: Option<TestFailure>,
+    },
+
     /// A custom test status.
     ///
     /// This is intended for cases where the built in status variants are not expressive enough.
@@ -110,11 +152,17 @@ impl TestStatus {
     /// The following statuses are treated as good:
     /// - [`Passed`](TestStatus::Passed)
     /// - [`Ignored`](TestStatus::Ignored)
+    /// - [`ExpectedFailure`](TestStatus::ExpectedFailure)
+    /// - [`Quarantined`](TestStatus::Quarantined)
     /// - [`Other`](TestStatus::Other)
     pub fn is_good(&self) -> bool {
         matches!(
             self,
-            TestStatus::Passed | TestStatus::Ignored { .. } | TestStatus::Other(_)
+            TestStatus::Passed
+                | TestStatus::Ignored { .. }
+                | TestStatus::ExpectedFailure(_)
+                | TestStatus::Quarantined { .. }
+                | TestStatus::Other(_)
         )
     }
 
@@ -139,7 +187,7 @@ impl TestStatus {
         matches!(self, TestStatus::TimedOut)
     }
 
-    /// Returns `true` if the test was ignored.
+    /// Returns `true` if the test was ignored, this includes tests that skipped themselves.
     pub fn ignored(&self) -> bool {
         matches!(self, TestStatus::Ignored { .. })
     }
@@ -148,6 +196,26 @@ impl TestStatus {
     pub fn failed(&self) -> bool {
         matches!(self, TestStatus::Failed(_))
     }
+
+    /// Returns `true` if the test failed as expected.
+    pub fn expected_failure(&self) -> bool {
+        matches!(self, TestStatus::ExpectedFailure(_))
+    }
+
+    /// Returns `true` if the test was expected to fail but passed.
+    pub fn unexpected_pass(&self) -> bool {
+        matches!(self, TestStatus::Failed(TestFailure::UnexpectedPass))
+    }
+
+    /// Returns `true` if the test is quarantined and failed.
+    pub fn quarantined(&self) -> bool {
+        matches!(self, TestStatus::Quarantined { failure: Some(_) })
+    }
+
+    /// Returns `true` if the test is quarantined but passed.
+    pub fn quarantined_pass(&self) -> bool {
+        matches!(self, TestStatus::Quarantined { failure: None })
+    }
 }
 
 /// Describes why a test failed.
@@ -165,9 +233,15 @@ pub enum TestFailure {
     Error(Whatever),
 
     /// The test panicked when it was not expected to.
-    ///
-    /// The contained string represents the panic payload formatted as text.
-    Panicked(String),
+    Panicked {
+        /// The panic payload formatted as text.
+        message: String,
+        /// Where the panic happened, if known.
+        ///
+        /// This is known if the panic hook recorded a [`PanicInfo`](crate::capture::PanicInfo)
+        /// for the panic, the default panic hook does.
+        location: Option<PanicLocation>,
+    },
 
     /// The test was expected to panic, but no panic occurred.
     ///
@@ -180,22 +254,42 @@ pub enum TestFailure {
 
     /// The test panicked, but the panic did not match the expectation.
     ///
-    /// This is used when a panic occurred, but the panic message did not match
+    /// This is used when a panic occurred, but the panic message or payload did not match
     /// the expected value.
     PanicMismatch {
         /// The panic message that was observed.
         got: String,
-        /// The expected panic message, if any.
+        /// The expected panic message, pattern or predicate description, if any.
         expected: Option<String>,
+        /// The rule of the expectation that failed.
+        rule: PanicRule,
     },
+
+    /// One or more soft assertions failed, see [`check`](mod@crate::check).
+    ///
+    /// The failures are listed in the order they were recorded.
+    Multiple(Vec<CheckFailure>),
+
+    /// The test was expected to fail, but passed.
+    ///
+    /// The expected failure marker of the test should be removed.
+    UnexpectedPass,
 }
 
 impl From<TestResult> for TestStatus {
     fn from(value: TestResult) -> Self {
         match value.0 {
             Ok(None) => TestStatus::Passed,
-            Ok(Some(details)) => TestStatus::Other(details),
-            Err(err) => TestStatus::Failed(TestFailure::Error(Whatever::from(err))),
+            Ok(Some(details)) => match details.as_any_ref().downcast_ref::<Skipped>() {
+                Some(skipped) => skipped.clone().report(),
+                None => TestStatus::Other(details),
+            },
+            Err(err) => {
+                TestStatus::Failed(match err.as_any_ref().downcast_ref::<CheckFailures>() {
+                    Some(CheckFailures(failures)) => TestFailure::Multiple(failures.clone()),
+                    None => TestFailure::Error(err),
+                })
+            }
         }
     }
 }
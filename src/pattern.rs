@@ -0,0 +1,641 @@
+//! Small built in text patterns.
+//!
+//! Kitest matches panic messages and test names against patterns without pulling in a regex
+//! engine as a dependency.
+//! This module provides two pattern types for that:
+//!
+//! - [`Glob`] matches the whole text against a shell like pattern:
+//!   `*` matches any sequence, `?` matches any single character, `[abc]`, `[a-z]` and `[!abc]`
+//!   match character classes and `\` escapes the next character.
+//! - [`Regex`] searches the text for a match of a regular expression subset:
+//!   literals, `.`, character classes (`[abc]`, `[a-z]`, `[^abc]`), the shorthands `\d`, `\w`,
+//!   `\s` and their negations, the quantifiers `*`, `+` and `?`, the anchors `^` and `$`, groups
+//!   `(...)` and alternation `|`.
+//!   Counted repetition, lazy quantifiers, captures and lookaround are not supported.
+//!
+//! Both work on characters, not bytes.
+//! Patterns are parsed once and matched without recursion, in time proportional to the length of
+//! the text times the length of the pattern.
+//! Globs only backtrack to the last `*`, regular expressions are compiled into an automaton that
+//! tracks all of its states at once.
+
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    iter::Peekable,
+    mem, slice,
+    str::Chars,
+};
+
+/// A glob pattern, see the [module documentation](self) for the syntax.
+#[derive(Debug, Clone)]
+pub struct Glob {
+    source: String,
+    tokens: Vec<GlobToken>,
+}
+
+impl Glob {
+    /// Parse a glob pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a character class is not closed, contains an invalid range, or the
+    /// pattern ends with a `\`.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser::new(pattern);
+        let mut tokens = Vec::new();
+        while let Some(c) = parser.next() {
+            let token = match c {
+                '*' if tokens.last() == Some(&GlobToken::Star) => continue,
+                '*' => GlobToken::Star,
+                '?' => GlobToken::Any,
+                '[' => GlobToken::Class(parser.class(&['!', '^'])?),
+                '\\' => GlobToken::Char(parser.next().ok_or(parser.error("trailing `\\`"))?),
+                c => GlobToken::Char(c),
+            };
+            tokens.push(token);
+        }
+
+        Ok(Self {
+            source: pattern.to_string(),
+            tokens,
+        })
+    }
+
+    /// Return `true` if the whole `text` matches this pattern.
+    pub fn is_match(&self, text: &str) -> bool {
+        let input: Vec<char> = text.chars().collect();
+        let (mut token, mut pos) = (0, 0);
+        // the token after the last `*` and the position that `*` currently extends to
+        let mut star = None;
+        while pos < input.len() {
+            match self.tokens.get(token) {
+                Some(GlobToken::Star) => {
+                    token += 1;
+                    star = Some((token, pos));
+                    continue;
+                }
+                Some(GlobToken::Char(c)) if *c == input[pos] => {}
+                Some(GlobToken::Any) => {}
+                Some(GlobToken::Class(class)) if class.matches(input[pos]) => {}
+                _ => {
+                    // let the last `*` match one more character and retry from there
+                    let Some((after_star, star_end)) = star else {
+                        return false;
+                    };
+                    token = after_star;
+                    pos = star_end + 1;
+                    star = Some((after_star, pos));
+                    continue;
+                }
+            }
+            token += 1;
+            pos += 1;
+        }
+        self.tokens[token..]
+            .iter()
+            .all(|token| *token == GlobToken::Star)
+    }
+
+    /// Return the source of this pattern.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Display for Glob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for Glob {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Glob {}
+
+/// A regular expression, see the [module documentation](self) for the supported subset.
+#[derive(Debug, Clone)]
+pub struct Regex {
+    source: String,
+    program: Vec<Inst>,
+}
+
+impl Regex {
+    /// Parse a regular expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the expression is malformed or uses unsupported syntax.
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parser = Parser::new(pattern);
+        let nodes = parser.alternation()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("unmatched `)`"));
+        }
+
+        let mut program = Vec::new();
+        compile(&nodes, &mut program);
+        program.push(Inst::Match);
+        Ok(Self {
+            source: pattern.to_string(),
+            program,
+        })
+    }
+
+    /// Return `true` if this expression matches anywhere in `text`.
+    ///
+    /// Use `^` and `$` to match the whole text.
+    pub fn is_match(&self, text: &str) -> bool {
+        let input: Vec<char> = text.chars().collect();
+        let mut current = States::new(self.program.len());
+        let mut next = States::new(self.program.len());
+        for pos in 0..=input.len() {
+            // a match may start at every position
+            if current.add(&self.program, 0, pos, input.len()) {
+                return true;
+            }
+            let Some(&c) = input.get(pos) else {
+                break;
+            };
+
+            for &pc in &current.list {
+                let matched = match &self.program[pc] {
+                    Inst::Char(expected) => *expected == c,
+                    Inst::Any => true,
+                    Inst::Class(class) => class.matches(c),
+                    _ => false,
+                };
+                if matched && next.add(&self.program, pc + 1, pos + 1, input.len()) {
+                    return true;
+                }
+            }
+            mem::swap(&mut current, &mut next);
+            next.clear();
+        }
+        false
+    }
+
+    /// Return the source of this expression.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Display for Regex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl PartialEq for Regex {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Eq for Regex {}
+
+/// An error from parsing a [`Glob`] or [`Regex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    /// The character offset in the pattern where the error was detected.
+    pub position: usize,
+    /// What went wrong.
+    pub message: &'static str,
+}
+
+impl Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for PatternError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum GlobToken {
+    Char(char),
+    Any,
+    Class(Class),
+    Star,
+}
+
+/// A parsed regular expression, compiled into [`Inst`]s.
+#[derive(Debug, Clone)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    /// A group, matching any of its branches.
+    Alt(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl Class {
+    fn shorthand(item: ClassItem) -> Self {
+        Self {
+            negated: false,
+            items: vec![item],
+        }
+    }
+
+    fn matches(&self, c: char) -> bool {
+        let matched = self.items.iter().any(|item| match *item {
+            ClassItem::Range(lo, hi) => (lo..=hi).contains(&c),
+            ClassItem::Digit(negated) => c.is_ascii_digit() != negated,
+            ClassItem::Word(negated) => (c.is_alphanumeric() || c == '_') != negated,
+            ClassItem::Space(negated) => c.is_whitespace() != negated,
+        });
+        matched != self.negated
+    }
+}
+
+struct Parser<'p> {
+    chars: Peekable<Chars<'p>>,
+    position: usize,
+}
+
+impl<'p> Parser<'p> {
+    fn new(pattern: &'p str) -> Self {
+        Self {
+            chars: pattern.chars().peekable(),
+            position: 0,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        self.position += 1;
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let eaten = self.peek() == Some(expected);
+        if eaten {
+            self.next();
+        }
+        eaten
+    }
+
+    fn error(&self, message: &'static str) -> PatternError {
+        PatternError {
+            position: self.position,
+            message,
+        }
+    }
+
+    /// Parse branches separated by `|`, a single branch is returned as is.
+    fn alternation(&mut self) -> Result<Vec<Node>, PatternError> {
+        let mut branches = vec![self.concatenation()?];
+        while self.eat('|') {
+            branches.push(self.concatenation()?);
+        }
+
+        Ok(match branches.len() {
+            1 => branches.pop().unwrap_or_default(),
+            _ => vec![Node::Alt(branches)],
+        })
+    }
+
+    fn concatenation(&mut self) -> Result<Vec<Node>, PatternError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek()
+            && c != '|'
+            && c != ')'
+        {
+            let node = self.atom()?;
+            let (min, max) = match self.peek() {
+                Some('*') => (0, None),
+                Some('+') => (1, None),
+                Some('?') => (0, Some(1)),
+                _ => {
+                    nodes.push(node);
+                    continue;
+                }
+            };
+            self.next();
+            if matches!(self.peek(), Some('*' | '+' | '?')) {
+                return Err(self.error("repeated quantifier"));
+            }
+            nodes.push(Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+            });
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self) -> Result<Node, PatternError> {
+        let c = self.next().ok_or(self.error("unexpected end"))?;
+        Ok(match c {
+            '(' => {
+                let group = self.alternation()?;
+                if !self.eat(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                match group.as_slice() {
+                    [Node::Alt(_)] => group.into_iter().next().unwrap_or(Node::Alt(Vec::new())),
+                    _ => Node::Alt(vec![group]),
+                }
+            }
+            '*' | '+' | '?' => return Err(self.error("nothing to repeat")),
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '[' => Node::Class(self.class(&['^'])?),
+            '\\' => match self.escape()? {
+                Escaped::Char(c) => Node::Char(c),
+                Escaped::Class(item) => Node::Class(Class::shorthand(item)),
+            },
+            c => Node::Char(c),
+        })
+    }
+
+    fn escape(&mut self) -> Result<Escaped, PatternError> {
+        let c = self.next().ok_or(self.error("trailing `\\`"))?;
+        Ok(match c {
+            'd' | 'D' => Escaped::Class(ClassItem::Digit(c == 'D')),
+            'w' | 'W' => Escaped::Class(ClassItem::Word(c == 'W')),
+            's' | 'S' => Escaped::Class(ClassItem::Space(c == 'S')),
+            'n' => Escaped::Char('\n'),
+            'r' => Escaped::Char('\r'),
+            't' => Escaped::Char('\t'),
+            c if c.is_alphanumeric() => return Err(self.error("unknown escape")),
+            c => Escaped::Char(c),
+        })
+    }
+
+    /// Parse a character class after its opening `[`.
+    fn class(&mut self, negations: &[char]) -> Result<Class, PatternError> {
+        let negated = self.peek().is_some_and(|c| negations.contains(&c));
+        if negated {
+            self.next();
+        }
+
+        let mut items = Vec::new();
+        loop {
+            let c = self.next().ok_or(self.error("unclosed character class"))?;
+            let lo = match c {
+                ']' if !items.is_empty() => break,
+                '\\' => match self.escape()? {
+                    Escaped::Char(c) => c,
+                    Escaped::Class(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                },
+                c => c,
+            };
+
+            let mut lookahead = self.chars.clone();
+            if lookahead.next() == Some('-') && !matches!(lookahead.next(), None | Some(']')) {
+                self.next();
+                let hi = match self.next() {
+                    Some('\\') => match self.escape()? {
+                        Escaped::Char(c) => c,
+                        Escaped::Class(_) => return Err(self.error("invalid range")),
+                    },
+                    Some(c) => c,
+                    None => return Err(self.error("unclosed character class")),
+                };
+                if hi < lo {
+                    return Err(self.error("invalid range"));
+                }
+                items.push(ClassItem::Range(lo, hi));
+            } else {
+                items.push(ClassItem::Range(lo, lo));
+            }
+        }
+
+        Ok(Class { negated, items })
+    }
+}
+
+enum Escaped {
+    Char(char),
+    Class(ClassItem),
+}
+
+/// An instruction of a compiled [`Regex`].
+#[derive(Debug, Clone)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    /// Continue at both instructions.
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+/// Append the instructions matching `nodes` to `program`.
+fn compile(nodes: &[Node], program: &mut Vec<Inst>) {
+    for node in nodes {
+        match node {
+            Node::Char(c) => program.push(Inst::Char(*c)),
+            Node::Any => program.push(Inst::Any),
+            Node::Class(class) => program.push(Inst::Class(class.clone())),
+            Node::Start => program.push(Inst::Start),
+            Node::End => program.push(Inst::End),
+            Node::Alt(branches) => {
+                let mut jumps = Vec::new();
+                for (idx, branch) in branches.iter().enumerate() {
+                    if idx + 1 == branches.len() {
+                        compile(branch, program);
+                        break;
+                    }
+
+                    let split = program.len();
+                    program.push(Inst::Split(split + 1, 0));
+                    compile(branch, program);
+                    jumps.push(program.len());
+                    program.push(Inst::Jump(0));
+                    program[split] = Inst::Split(split + 1, program.len());
+                }
+
+                let end = program.len();
+                for jump in jumps {
+                    program[jump] = Inst::Jump(end);
+                }
+            }
+            Node::Repeat { node, min, max } => {
+                let node = slice::from_ref(node.as_ref());
+                for _ in 0..*min {
+                    compile(node, program);
+                }
+
+                match max {
+                    None => {
+                        let split = program.len();
+                        program.push(Inst::Split(split + 1, 0));
+                        compile(node, program);
+                        program.push(Inst::Jump(split));
+                        program[split] = Inst::Split(split + 1, program.len());
+                    }
+                    Some(max) => {
+                        for _ in *min..*max {
+                            let split = program.len();
+                            program.push(Inst::Split(split + 1, 0));
+                            compile(node, program);
+                            program[split] = Inst::Split(split + 1, program.len());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The states of a [`Regex`] that are active at one position of the text.
+struct States {
+    /// The instructions that consume the next character.
+    list: Vec<usize>,
+    seen: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+impl States {
+    fn new(len: usize) -> Self {
+        Self {
+            list: Vec::new(),
+            seen: vec![false; len],
+            stack: Vec::new(),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.list.clear();
+        self.seen.fill(false);
+    }
+
+    /// Add the state at `pc` and every state reachable from it without consuming a character.
+    ///
+    /// Returns `true` if one of them is a match.
+    fn add(&mut self, program: &[Inst], pc: usize, pos: usize, len: usize) -> bool {
+        self.stack.push(pc);
+        while let Some(pc) = self.stack.pop() {
+            if mem::replace(&mut self.seen[pc], true) {
+                continue;
+            }
+
+            match program[pc] {
+                Inst::Split(first, second) => {
+                    self.stack.push(second);
+                    self.stack.push(first);
+                }
+                Inst::Jump(target) => self.stack.push(target),
+                Inst::Start if pos == 0 => self.stack.push(pc + 1),
+                Inst::End if pos == len => self.stack.push(pc + 1),
+                Inst::Start | Inst::End => {}
+                Inst::Match => {
+                    self.stack.clear();
+                    return true;
+                }
+                Inst::Char(_) | Inst::Any | Inst::Class(_) => self.list.push(pc),
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_matches_whole_text() {
+        let glob = Glob::new("net::*::t?st_[a-c0-9]").unwrap();
+        assert!(glob.is_match("net::tcp::test_a"));
+        assert!(glob.is_match("net::::tast_7"));
+        assert!(!glob.is_match("net::tcp::test_d"));
+        assert!(!glob.is_match("my::net::tcp::test_a"));
+
+        let glob = Glob::new(r"[!x]\*").unwrap();
+        assert!(glob.is_match("a*"));
+        assert!(!glob.is_match("x*"));
+        assert!(!glob.is_match("ab"));
+
+        assert_eq!(
+            Glob::new("[abc").unwrap_err(),
+            PatternError {
+                position: 4,
+                message: "unclosed character class"
+            }
+        );
+    }
+
+    #[test]
+    fn regex_searches_text() {
+        let regex = Regex::new(r"^(foo|ba[rz])+\d?$").unwrap();
+        assert!(regex.is_match("foobar"));
+        assert!(regex.is_match("bazfoo1"));
+        assert!(!regex.is_match("foobar12"));
+        assert!(!regex.is_match(""));
+
+        let regex = Regex::new(r"index \d+ out of (range|bounds)").unwrap();
+        assert!(regex.is_match("panicked: index 12 out of range for slice"));
+        assert!(!regex.is_match("index out of range"));
+
+        let regex = Regex::new(r"a.c|[^\s\d]x*y").unwrap();
+        assert!(regex.is_match("abc"));
+        assert!(regex.is_match("_y"));
+        assert!(regex.is_match("-xxxy"));
+        assert!(!regex.is_match("1y"));
+
+        assert!(Regex::new("(a*)+b").unwrap().is_match("aab"));
+        assert!(Regex::new("(a*)+$").unwrap().is_match(""));
+        assert!(Regex::new("x(a|b|)y").unwrap().is_match("xy"));
+        assert!(!Regex::new(r"\d").unwrap().is_match("٣"));
+    }
+
+    #[test]
+    fn long_texts_do_not_overflow_the_stack() {
+        let text = "x".repeat(100_000);
+        assert!(!Glob::new("*y").unwrap().is_match(&text));
+        assert!(Glob::new("*x*x").unwrap().is_match(&text));
+        assert!(!Regex::new("x*y").unwrap().is_match(&text));
+        assert!(Regex::new("^(x|y)+$").unwrap().is_match(&text));
+    }
+
+    #[test]
+    fn regex_errors_point_at_problem() {
+        let error = |pattern| Regex::new(pattern).unwrap_err();
+        assert_eq!(error("a(b").message, "unclosed group");
+        assert_eq!(error("ab)").position, 2);
+        assert_eq!(error("*a").message, "nothing to repeat");
+        assert_eq!(error("a**").message, "repeated quantifier");
+        assert_eq!(error(r"\q").message, "unknown escape");
+        assert_eq!(error("[z-a]").message, "invalid range");
+    }
+}
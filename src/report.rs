@@ -60,7 +60,8 @@ impl<'t, FmtError: 't> TestReport<'t, FmtError> {
     ///
     /// The exit code is determined as follows:
     ///
-    /// - If any test failed, the exit code is [`ExitCode::FAILURE`]
+    /// - If any test failed, the exit code is [`ExitCode::FAILURE`],
+    ///   [quarantined](crate::outcome::TestStatus::Quarantined) tests do not count as failed
     /// - Otherwise, if any formatter errors occurred, the exit code is
     ///   [`ExitCode::FAILURE`]
     /// - Otherwise, the exit code is [`ExitCode::SUCCESS`]
@@ -132,7 +133,8 @@ impl<'t, GroupKey, GroupCtx, FmtError: 't> GroupedTestReport<'t, GroupKey, Group
     ///
     /// The exit code is determined as follows:
     ///
-    /// - If any test in any group failed, the exit code is [`ExitCode::FAILURE`]
+    /// - If any test in any group failed, the exit code is [`ExitCode::FAILURE`],
+    ///   [quarantined](crate::outcome::TestStatus::Quarantined) tests do not count as failed
     /// - Otherwise, if any formatter errors occurred, the exit code is
     ///   [`ExitCode::FAILURE`]
     /// - Otherwise, the exit code is [`ExitCode::SUCCESS`]
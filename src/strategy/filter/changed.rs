@@ -0,0 +1,310 @@
+use std::{
+    fs,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use crate::{
+    filter::{FilterExclusion, FilteredTests, TestFilter},
+    test::{Test, TestMeta, TestOrigin},
+};
+
+/// A mapping from changed files to the tests they affect, used by [`ChangedFilesFilter`].
+///
+/// This relates tests to changes beyond their own origin file, for example tests in `tests/`
+/// to the source modules they cover.
+///
+/// Implemented for [`NoMapping`] and for closures `Fn(&str, &TestMeta<Extra>) -> bool`.
+pub trait ChangeMapping<Extra> {
+    /// Return whether a change of the file at `changed` affects the test.
+    ///
+    /// `changed` is normalized to `/` separators without a leading `./`.
+    fn affects(&self, changed: &str, meta: &TestMeta<Extra>) -> bool;
+}
+
+/// A [`ChangeMapping`] relating tests only to their origin file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NoMapping;
+
+impl<Extra> ChangeMapping<Extra> for NoMapping {
+    fn affects(&self, _: &str, _: &TestMeta<Extra>) -> bool {
+        false
+    }
+}
+
+impl<Extra, F> ChangeMapping<Extra> for F
+where
+    F: Fn(&str, &TestMeta<Extra>) -> bool,
+{
+    fn affects(&self, changed: &str, meta: &TestMeta<Extra>) -> bool {
+        self(changed, meta)
+    }
+}
+
+/// A [`TestFilter`] keeping the tests related to a set of changed files.
+///
+/// A test is kept if the file of its [`TestOrigin::TextFile`] origin is one of the changed
+/// files, or if the [mapping](Self::with_mapping) relates it to one.
+/// Tests without a file origin are only kept through the mapping.
+///
+/// The origin file of a test is resolved against the [root](Self::with_root) and must then equal
+/// a changed path.
+/// Cargo builds workspace members with [`file!`] paths relative to the workspace root, so the
+/// default empty root fits changed paths relative to the workspace root, like the ones from
+/// `git diff --name-only` in a repository with the workspace at its top.
+/// For a workspace in a subdirectory of the repository, set the root to that subdirectory.
+///
+/// With [`with_fallback_to_all`](Self::with_fallback_to_all), a changed file that is related to
+/// no test keeps all tests.
+/// A change to a build script, a fixture or a manifest may affect any test.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ChangedFilesFilter<Mapping = NoMapping> {
+    changed: Vec<String>,
+    root: String,
+    mapping: Mapping,
+    fallback_to_all: bool,
+}
+
+impl ChangedFilesFilter {
+    /// Create a filter for the given changed paths.
+    pub fn new(changed: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            changed: changed
+                .into_iter()
+                .map(|path| normalize(path.as_ref()))
+                .filter(|path| !path.is_empty())
+                .collect(),
+            root: String::new(),
+            mapping: NoMapping,
+            fallback_to_all: false,
+        }
+    }
+
+    /// Read changed paths from a reader, one per line.
+    ///
+    /// Surrounding whitespace and empty lines are ignored.
+    /// Pass [`io::stdin().lock()`](io::stdin) to read the paths from stdin, for example piped
+    /// from `git diff --name-only`.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let lines = reader.lines().collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::new(lines.iter().map(|line| line.trim())))
+    }
+
+    /// Read changed paths from a file, one per line.
+    ///
+    /// See [`from_reader`](Self::from_reader).
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(Self::new(content.lines().map(str::trim)))
+    }
+}
+
+impl<Mapping> ChangedFilesFilter<Mapping> {
+    /// Relate tests to changed files beyond their origin file.
+    ///
+    /// This replaces the previous mapping.
+    pub fn with_mapping<WithMapping>(
+        self,
+        mapping: WithMapping,
+    ) -> ChangedFilesFilter<WithMapping> {
+        ChangedFilesFilter {
+            changed: self.changed,
+            root: self.root,
+            mapping,
+            fallback_to_all: self.fallback_to_all,
+        }
+    }
+
+    /// Set the directory the origin files of tests are relative to, relative to the base of the
+    /// changed paths.
+    ///
+    /// This replaces the previous root, the default is empty.
+    pub fn with_root(self, root: impl AsRef<str>) -> Self {
+        Self {
+            root: normalize(root.as_ref()).trim_end_matches('/').to_string(),
+            ..self
+        }
+    }
+
+    /// Set whether a changed file that is related to no test keeps all tests.
+    ///
+    /// This replaces the previous `fallback_to_all` value.
+    pub fn with_fallback_to_all(self, fallback_to_all: bool) -> Self {
+        Self {
+            fallback_to_all,
+            ..self
+        }
+    }
+
+    /// Return the changed paths, normalized to `/` separators without a leading `./`.
+    pub fn changed(&self) -> &[String] {
+        &self.changed
+    }
+
+    fn affects<Extra>(&self, changed: &str, meta: &TestMeta<Extra>) -> bool
+    where
+        Mapping: ChangeMapping<Extra>,
+    {
+        let origin_changed = match &meta.origin {
+            Some(TestOrigin::TextFile { file, .. }) => self.resolve(file) == changed,
+            _ => false,
+        };
+        origin_changed || self.mapping.affects(changed, meta)
+    }
+
+    /// Resolve the origin file of a test against the root.
+    fn resolve(&self, file: &str) -> String {
+        let file = normalize(file);
+        match self.root.is_empty() {
+            true => file,
+            false => format!("{}/{file}", self.root),
+        }
+    }
+
+    /// Mark the kept tests, all tests if a changed file is unrelated and the fallback is set.
+    fn selection<Extra>(&self, tests: &[Test<Extra>]) -> Vec<bool>
+    where
+        Mapping: ChangeMapping<Extra>,
+    {
+        let mut selected = vec![false; tests.len()];
+        for changed in &self.changed {
+            let mut related = false;
+            for (test, selected) in tests.iter().zip(selected.iter_mut()) {
+                if self.affects(changed, test) {
+                    *selected = true;
+                    related = true;
+                }
+            }
+            if self.fallback_to_all && !related {
+                return vec![true; tests.len()];
+            }
+        }
+        selected
+    }
+}
+
+impl<Extra, Mapping: ChangeMapping<Extra>> TestFilter<Extra> for ChangedFilesFilter<Mapping> {
+    fn filter<'t>(
+        &self,
+        tests: &'t [Test<Extra>],
+    ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+        let remaining: Vec<_> = tests
+            .iter()
+            .zip(self.selection(tests))
+            .filter_map(|(test, keep)| keep.then_some(test))
+            .collect();
+        FilteredTests {
+            filtered_out: tests.len() - remaining.len(),
+            tests: remaining.into_iter(),
+        }
+    }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        tests
+            .iter()
+            .zip(self.selection(tests))
+            .filter(|(_, keep)| !keep)
+            .map(|(test, _)| {
+                let reason = match &test.origin {
+                    Some(TestOrigin::TextFile { file, .. }) => {
+                        format!("origin `{file}` is not related to a changed file")
+                    }
+                    _ => "no file origin related to a changed file".to_string(),
+                };
+                FilterExclusion {
+                    test,
+                    reason: reason.into(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn normalize(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut path = path.as_str();
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    path.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+    use crate::test_support::*;
+
+    fn in_file(name: &'static str, file: &'static str) -> Test {
+        let mut test = test! {name: name};
+        test.meta.origin = Some(TestOrigin::TextFile {
+            file: Cow::Borrowed(file),
+            line: 1,
+            column: 1,
+        });
+        test
+    }
+
+    fn kept(tests: &[Test], filter: impl TestFilter<()>) -> Vec<&str> {
+        filter
+            .filter(tests)
+            .tests
+            .map(|test| test.name.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_tests_of_changed_files() {
+        let tests = &[
+            in_file("parser", "src/parser.rs"),
+            in_file("lexer", "src\\lexer.rs"),
+            in_file("cli", "tests/cli.rs"),
+            test! {name: "custom"},
+        ];
+
+        let changed = "./src/lexer.rs\n\n  tests/cli.rs \n".as_bytes();
+        let filter = ChangedFilesFilter::from_reader(changed).unwrap();
+        assert_eq!(filter.changed(), ["src/lexer.rs", "tests/cli.rs"]);
+        assert_eq!(kept(tests, filter.clone()), ["lexer", "cli"]);
+        assert_eq!(
+            filter.explain(tests)[0].reason,
+            "origin `src/parser.rs` is not related to a changed file"
+        );
+
+        let mapped = ChangedFilesFilter::new(["src/parser.rs", "src/parser/ast.rs"]).with_mapping(
+            |changed: &str, meta: &TestMeta| {
+                changed.starts_with("src/parser") && meta.name == "custom"
+            },
+        );
+        assert_eq!(kept(tests, mapped), ["parser", "custom"]);
+
+        let unrelated = ChangedFilesFilter::new(["Cargo.toml", "src/parser.rs"]);
+        assert_eq!(kept(tests, unrelated.clone()), ["parser"]);
+        assert_eq!(kept(tests, unrelated.with_fallback_to_all(true)).len(), 4);
+        assert!(kept(tests, ChangedFilesFilter::new(["lexer.rs.bak"])).is_empty());
+    }
+
+    #[test]
+    fn matches_workspace_members_by_root() {
+        let tests = &[
+            in_file("a", "crates/a/src/lib.rs"),
+            in_file("b", "crates/b/src/lib.rs"),
+            in_file("root", "src/lib.rs"),
+        ];
+
+        assert_eq!(
+            kept(tests, ChangedFilesFilter::new(["crates/a/src/lib.rs"])),
+            ["a"]
+        );
+        assert_eq!(
+            kept(tests, ChangedFilesFilter::new(["src/lib.rs"])),
+            ["root"]
+        );
+        assert!(kept(tests, ChangedFilesFilter::new(["lib.rs"])).is_empty());
+
+        let nested = ChangedFilesFilter::new(["rust/crates/b/src/lib.rs"]);
+        assert!(kept(tests, nested.clone()).is_empty());
+        assert_eq!(kept(tests, nested.with_root("./rust/")), ["b"]);
+    }
+}
@@ -0,0 +1,268 @@
+use std::borrow::Cow;
+
+use crate::{
+    filter::{FilterExclusion, FilteredTests, TestFilter, position, selection},
+    test::Test,
+};
+
+/// A [`TestFilter`] keeping the tests kept by both filters.
+///
+/// Both filters see all tests.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A, B> And<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self(a, b)
+    }
+}
+
+impl<Extra, A, B> TestFilter<Extra> for And<A, B>
+where
+    A: TestFilter<Extra>,
+    B: TestFilter<Extra>,
+{
+    fn filter<'t>(
+        &self,
+        tests: &'t [Test<Extra>],
+    ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+        let a = selection(tests, self.0.filter(tests).tests);
+        let b = selection(tests, self.1.filter(tests).tests);
+        select(tests, a.into_iter().zip(b).map(|(a, b)| a && b).collect())
+    }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        let a = reasons(tests, self.0.explain(tests));
+        let b = reasons(tests, self.1.explain(tests));
+        exclusions(tests, a.into_iter().zip(b).map(|(a, b)| a.or(b)))
+    }
+}
+
+/// A [`TestFilter`] keeping the tests kept by any of the two filters.
+///
+/// Tests keep their order from the input, tests kept by both filters are included once.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A, B> Or<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self(a, b)
+    }
+}
+
+impl<Extra, A, B> TestFilter<Extra> for Or<A, B>
+where
+    A: TestFilter<Extra>,
+    B: TestFilter<Extra>,
+{
+    fn filter<'t>(
+        &self,
+        tests: &'t [Test<Extra>],
+    ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+        let a = selection(tests, self.0.filter(tests).tests);
+        let b = selection(tests, self.1.filter(tests).tests);
+        select(tests, a.into_iter().zip(b).map(|(a, b)| a || b).collect())
+    }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        let a = reasons(tests, self.0.explain(tests));
+        let b = reasons(tests, self.1.explain(tests));
+        exclusions(
+            tests,
+            a.into_iter().zip(b).map(|(a, b)| match (a, b) {
+                (Some(a), Some(b)) => Some(Cow::Owned(format!("{a} and {b}"))),
+                _ => None,
+            }),
+        )
+    }
+}
+
+/// A [`TestFilter`] keeping exactly the tests the inner filter filters out.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Not<F>(pub F);
+
+impl<F> Not<F> {
+    pub fn new(filter: F) -> Self {
+        Self(filter)
+    }
+}
+
+impl<Extra, F> TestFilter<Extra> for Not<F>
+where
+    F: TestFilter<Extra>,
+{
+    fn filter<'t>(
+        &self,
+        tests: &'t [Test<Extra>],
+    ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+        let kept = selection(tests, self.0.filter(tests).tests);
+        select(tests, kept.into_iter().map(|kept| !kept).collect())
+    }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        let kept = selection(tests, self.0.filter(tests).tests);
+        exclusions(
+            tests,
+            kept.into_iter()
+                .map(|kept| kept.then_some(Cow::Borrowed("matched a negated filter"))),
+        )
+    }
+}
+
+/// Index the reasons of `exclusions` by the position of their test.
+fn reasons<Extra>(
+    tests: &[Test<Extra>],
+    exclusions: Vec<FilterExclusion<'_, Extra>>,
+) -> Vec<Option<Cow<'static, str>>> {
+    let mut reasons = vec![None; tests.len()];
+    for exclusion in exclusions {
+        reasons[position(tests, exclusion.test)] = Some(exclusion.reason);
+    }
+    reasons
+}
+
+fn exclusions<'t, Extra>(
+    tests: &'t [Test<Extra>],
+    reasons: impl IntoIterator<Item = Option<Cow<'static, str>>>,
+) -> Vec<FilterExclusion<'t, Extra>> {
+    tests
+        .iter()
+        .zip(reasons)
+        .filter_map(|(test, reason)| {
+            Some(FilterExclusion {
+                test,
+                reason: reason?,
+            })
+        })
+        .collect()
+}
+
+/// Keep the tests marked in `selected`.
+fn select<'t, Extra>(
+    tests: &'t [Test<Extra>],
+    selected: Vec<bool>,
+) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+    let remaining: Vec<_> = tests
+        .iter()
+        .zip(selected)
+        .filter_map(|(test, keep)| keep.then_some(test))
+        .collect();
+    FilteredTests {
+        filtered_out: tests.len() - remaining.len(),
+        tests: remaining.into_iter(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filter::DefaultFilter, test_support::*};
+
+    /// Keeps every other test of the slice it is given.
+    struct EveryOther;
+
+    impl TestFilter<()> for EveryOther {
+        fn filter<'t>(
+            &self,
+            tests: &'t [Test],
+        ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test>, ()> {
+            select(tests, (0..tests.len()).map(|i| i % 2 == 0).collect())
+        }
+    }
+
+    fn names(tests: &[Test], filter: impl TestFilter<()>) -> (Vec<&str>, usize, usize) {
+        let filtered = filter.filter(tests);
+        let len = filtered.tests.len();
+        let names = filtered.tests.map(|test| test.meta.name.as_ref()).collect();
+        (names, len, filtered.filtered_out)
+    }
+
+    #[test]
+    fn filters_combine() {
+        let tests = &[
+            test! {name: "a::one"},
+            test! {name: "b::two"},
+            test! {name: "a::three"},
+            test! {name: "a::four"},
+        ];
+        let a = || DefaultFilter::default().with_filter(["a::"]);
+        let two = || DefaultFilter::default().with_filter(["two", "four"]);
+
+        assert_eq!(names(tests, And(a(), two())), (vec!["a::four"], 1, 3));
+        assert_eq!(
+            names(tests, Or(a(), two())),
+            (vec!["a::one", "b::two", "a::three", "a::four"], 4, 0)
+        );
+        assert_eq!(names(tests, Not(a())), (vec!["b::two"], 1, 3));
+        assert_eq!(
+            names(tests, And(a(), EveryOther)),
+            (vec!["a::one", "a::three"], 2, 2)
+        );
+        assert_eq!(
+            names(tests, Or(Not(EveryOther), And(two(), Not(a())))),
+            (vec!["b::two", "a::four"], 2, 2)
+        );
+    }
+
+    #[test]
+    fn explanations_combine() {
+        let tests = &[
+            test! {name: "a::one"},
+            test! {name: "b::two"},
+            test! {name: "a::three"},
+        ];
+        let a = || DefaultFilter::default().with_filter(["a::"]);
+        let skip = || DefaultFilter::default().with_skip(["one"]);
+        let explain = |filter: &dyn Fn(&[Test]) -> Vec<FilterExclusion<'_, ()>>| {
+            filter(tests)
+                .into_iter()
+                .map(|exclusion| (exclusion.test.meta.name.to_string(), exclusion.reason))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            explain(&|tests| And(a(), skip()).explain(tests)),
+            [
+                ("a::one".into(), "matched skip `one`".into()),
+                ("b::two".into(), "did not match filter `a::`".into()),
+            ]
+        );
+        assert_eq!(
+            explain(&|tests| Or(Not(skip()), a()).explain(tests)),
+            [(
+                "b::two".into(),
+                "matched a negated filter and did not match filter `a::`".into()
+            )]
+        );
+        assert_eq!(
+            explain(&|tests| And(skip(), EveryOther).explain(tests)),
+            [
+                ("a::one".into(), "matched skip `one`".into()),
+                ("b::two".into(), "filtered out".into()),
+            ]
+        );
+    }
+
+    /// Yields a test that is not part of the filtered slice.
+    struct Foreign;
+
+    impl TestFilter<()> for Foreign {
+        fn filter<'t>(
+            &self,
+            _: &'t [Test],
+        ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test>, ()> {
+            let foreign: &'static Test = Box::leak(Box::new(test! {name: "foreign"}));
+            FilteredTests {
+                tests: [foreign].into_iter(),
+                filtered_out: 0,
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "not part of the filtered slice")]
+    fn foreign_tests_are_rejected() {
+        let tests = &[test! {name: "a::one"}];
+        let _ = Not(Foreign).filter(tests);
+    }
+}
@@ -1,7 +1,12 @@
-use std::{slice, vec};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    slice, vec,
+};
 
 use crate::{
-    filter::{FilteredTests, TestFilter},
+    filter::{FilterExclusion, FilteredTests, TestFilter},
+    pattern::{Glob, PatternError, Regex},
     test::Test,
 };
 
@@ -16,14 +21,98 @@ use crate::{
 ///
 /// By default, `exact` is `false`, so `filter` and `skip` entries are treated as
 /// substrings of the test name.
+///
+/// With [`with_filter_patterns`](Self::with_filter_patterns) and
+/// [`with_skip_patterns`](Self::with_skip_patterns), entries may also be patterns, selected per
+/// entry by a prefix:
+/// - `glob:` matches the whole test name against a [`Glob`], like `glob:parser::*::roundtrip_*`
+/// - `re:` searches the test name for a match of a [`Regex`], like `re:^parser::.*_(de|en)code$`
+///
+/// Entries without a prefix keep matching as substrings or exactly.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct DefaultFilter {
     exact: bool,
-    filter: Vec<String>,
-    skip: Vec<String>,
+    filter: Vec<FilterEntry>,
+    skip: Vec<FilterEntry>,
     only_ignored: bool,
 }
 
+/// A `filter` or `skip` entry of a [`DefaultFilter`] that is not a valid pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidFilterPattern {
+    /// The entry, including its prefix.
+    pub entry: String,
+    /// Why the pattern could not be parsed.
+    pub error: PatternError,
+}
+
+impl fmt::Display for InvalidFilterPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid filter pattern {:?}: {}", self.entry, self.error)
+    }
+}
+
+impl Error for InvalidFilterPattern {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FilterEntry {
+    Name(String),
+    Glob(Glob),
+    Regex(Regex),
+}
+
+impl FilterEntry {
+    fn pattern(entry: String) -> Result<Self, InvalidFilterPattern> {
+        let parsed = if let Some(glob) = entry.strip_prefix("glob:") {
+            Glob::new(glob).map(FilterEntry::Glob)
+        } else if let Some(regex) = entry.strip_prefix("re:") {
+            Regex::new(regex).map(FilterEntry::Regex)
+        } else {
+            return Ok(FilterEntry::Name(entry));
+        };
+        parsed.map_err(|error| InvalidFilterPattern { entry, error })
+    }
+
+    fn is_match(&self, name: &str, exact: bool) -> bool {
+        match self {
+            FilterEntry::Name(entry) if exact => name == entry,
+            FilterEntry::Name(entry) => name.contains(entry.as_str()),
+            FilterEntry::Glob(glob) => glob.is_match(name),
+            FilterEntry::Regex(regex) => regex.is_match(name),
+        }
+    }
+}
+
+impl Display for FilterEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterEntry::Name(entry) => f.write_str(entry),
+            FilterEntry::Glob(glob) => write!(f, "glob:{glob}"),
+            FilterEntry::Regex(regex) => write!(f, "re:{regex}"),
+        }
+    }
+}
+
+fn names(entries: impl IntoIterator<Item = impl Into<String>>) -> Vec<FilterEntry> {
+    entries
+        .into_iter()
+        .map(|entry| FilterEntry::Name(entry.into()))
+        .collect()
+}
+
+fn patterns(
+    entries: impl IntoIterator<Item = impl Into<String>>,
+) -> Result<Vec<FilterEntry>, InvalidFilterPattern> {
+    entries
+        .into_iter()
+        .map(|entry| FilterEntry::pattern(entry.into()))
+        .collect()
+}
+
 impl DefaultFilter {
     /// Set whether filter and skip entries must match test names exactly.
     ///
@@ -44,17 +133,38 @@ impl DefaultFilter {
     /// This replaces the previous filter list.
     pub fn with_filter(self, filter: impl IntoIterator<Item = impl Into<String>>) -> Self {
         Self {
-            filter: filter.into_iter().map(Into::into).collect(),
+            filter: names(filter),
             ..self
         }
     }
 
+    /// Replace the current inclusion filter list with entries that may be patterns.
+    ///
+    /// Entries starting with `glob:` or `re:` are parsed as [`Glob`] or [`Regex`], see the
+    /// [type documentation](Self).
+    /// Other entries work like the ones of [`with_filter`](Self::with_filter).
+    ///
+    /// This replaces the previous filter list.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first entry that is not a valid pattern.
+    pub fn with_filter_patterns(
+        self,
+        filter: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self, InvalidFilterPattern> {
+        Ok(Self {
+            filter: patterns(filter)?,
+            ..self
+        })
+    }
+
     /// Append entries to the inclusion filter list.
     ///
     /// If the filter list is empty, filtering is effectively disabled and all tests
     /// are allowed through (unless they are skipped).
     pub fn append_filter(&mut self, filter: impl IntoIterator<Item = impl Into<String>>) {
-        self.filter.extend(filter.into_iter().map(Into::into));
+        self.filter.extend(names(filter));
     }
 
     /// Replace the current skip list.
@@ -65,17 +175,36 @@ impl DefaultFilter {
     /// This replaces the previous skip list.
     pub fn with_skip(self, skip: impl IntoIterator<Item = impl Into<String>>) -> Self {
         Self {
-            skip: skip.into_iter().map(Into::into).collect(),
+            skip: names(skip),
             ..self
         }
     }
 
+    /// Replace the current skip list with entries that may be patterns.
+    ///
+    /// Entries are parsed like the ones of [`with_filter_patterns`](Self::with_filter_patterns).
+    ///
+    /// This replaces the previous skip list.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first entry that is not a valid pattern.
+    pub fn with_skip_patterns(
+        self,
+        skip: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self, InvalidFilterPattern> {
+        Ok(Self {
+            skip: patterns(skip)?,
+            ..self
+        })
+    }
+
     /// Append entries to the skip list.
     ///
     /// Skip entries remove matching tests from the run, even if they also match the
     /// inclusion filter.
     pub fn append_skip(&mut self, skip: impl IntoIterator<Item = impl Into<String>>) {
-        self.skip.extend(skip.into_iter().map(Into::into));
+        self.skip.extend(names(skip));
     }
 
     /// Set whether only ignored tests should be executed.
@@ -134,57 +263,12 @@ impl<Extra> TestFilter<Extra> for DefaultFilter {
             };
         }
 
-        if self.exact {
-            let mut remaining = Vec::new();
-            let mut filtered = 0;
-            for meta in tests {
-                let name = meta.name.as_ref();
-                let in_filter =
-                    self.filter.is_empty() || self.filter.iter().any(|filter| name == filter);
-
-                if !in_filter {
-                    filtered += 1;
-                    continue;
-                }
-
-                if self.only_ignored && !meta.ignore.ignored() {
-                    filtered += 1;
-                    continue;
-                }
-
-                let skipped = self.skip.iter().any(|skip| name == skip);
-                match skipped {
-                    true => filtered += 1,
-                    false => remaining.push(meta),
-                }
-            }
-            return FilteredTests {
-                tests: DefaultFilterIterator::Vec(remaining.into_iter()),
-                filtered_out: filtered,
-            };
-        }
-
         let mut remaining = Vec::new();
         let mut filtered = 0;
-        for meta in tests {
-            let name = meta.name.as_ref();
-            let in_filter =
-                self.filter.is_empty() || self.filter.iter().any(|filter| name.contains(filter));
-
-            if !in_filter {
-                filtered += 1;
-                continue;
-            }
-
-            if self.only_ignored && !meta.ignore.ignored() {
-                filtered += 1;
-                continue;
-            }
-
-            let skipped = self.skip.iter().any(|skip| name.contains(skip));
-            match skipped {
-                true => filtered += 1,
-                false => remaining.push(meta),
+        for test in tests {
+            match self.exclusion(test) {
+                Some(_) => filtered += 1,
+                None => remaining.push(test),
             }
         }
 
@@ -193,14 +277,67 @@ impl<Extra> TestFilter<Extra> for DefaultFilter {
             filtered_out: filtered,
         }
     }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        tests
+            .iter()
+            .filter_map(|test| {
+                let reason = match self.exclusion(test)? {
+                    Exclusion::NoFilterMatch => match self.filter.as_slice() {
+                        [entry] => format!("did not match filter `{entry}`"),
+                        entries => {
+                            let entries: Vec<_> = entries.iter().map(ToString::to_string).collect();
+                            format!("did not match any filter of `{}`", entries.join("`, `"))
+                        }
+                    },
+                    Exclusion::NotIgnored => "not ignored".to_string(),
+                    Exclusion::Skip(entry) => format!("matched skip `{entry}`"),
+                };
+                Some(FilterExclusion {
+                    test,
+                    reason: reason.into(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Why a [`DefaultFilter`] excludes a test.
+enum Exclusion<'f> {
+    NoFilterMatch,
+    NotIgnored,
+    Skip(&'f FilterEntry),
+}
+
+impl DefaultFilter {
+    fn exclusion<Extra>(&self, test: &Test<Extra>) -> Option<Exclusion<'_>> {
+        let name = test.name.as_ref();
+        if !self.filter.is_empty()
+            && !self
+                .filter
+                .iter()
+                .any(|filter| filter.is_match(name, self.exact))
+        {
+            return Some(Exclusion::NoFilterMatch);
+        }
+
+        if self.only_ignored && !test.ignore.ignored() {
+            return Some(Exclusion::NotIgnored);
+        }
+
+        self.skip
+            .iter()
+            .find(|skip| skip.is_match(name, self.exact))
+            .map(Exclusion::Skip)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::{collections::HashSet, process::ExitCode};
 
     use super::*;
-    use crate::test_support::*;
+    use crate::{formatter::pretty::PrettyFormatter, test_support::*};
 
     #[test]
     fn empty_filter_allows_everything() {
@@ -277,4 +414,86 @@ mod tests {
         assert!(!names.contains("crazy_test"));
         assert!(names.contains("not_so_cool_test"));
     }
+
+    #[test]
+    fn pattern_entries_work() {
+        let tests = &[
+            test! {name: "parser::json::roundtrip_numbers"},
+            test! {name: "parser::json::parse_numbers"},
+            test! {name: "parser::toml::roundtrip_tables"},
+            test! {name: "parser::toml::roundtrip_slow"},
+            test! {name: "lexer::roundtrip_tokens"},
+            test! {name: "glob:literal"},
+        ];
+
+        let filter = DefaultFilter::default()
+            .with_filter_patterns(["glob:parser::*::roundtrip_*", "glob:literal"])
+            .unwrap()
+            .with_skip_patterns([r"re:_slo\w$"])
+            .unwrap();
+
+        let report = harness(tests).with_filter(filter).run();
+        let names: Vec<_> = report.outcomes.into_iter().map(|(n, _)| n).collect();
+        assert_eq!(
+            names,
+            [
+                "parser::json::roundtrip_numbers",
+                "parser::toml::roundtrip_tables"
+            ]
+        );
+
+        let report = harness(tests)
+            .with_filter(DefaultFilter::default().with_filter(["glob:lit"]))
+            .run();
+        assert_eq!(report.outcomes.len(), 1);
+
+        let invalid = DefaultFilter::default().with_skip_patterns(["ok", "re:(unclosed"]);
+        assert_eq!(invalid.unwrap_err().entry, "re:(unclosed");
+        let invalid = DefaultFilter::default().with_filter_patterns(["glob:[a"]);
+        assert_eq!(
+            invalid.unwrap_err().to_string(),
+            "invalid filter pattern \"glob:[a\": unclosed character class at position 2"
+        );
+    }
+
+    #[test]
+    fn explain_lists_reasons() {
+        let tests = &[
+            test! {name: "parser::json"},
+            test! {name: "parser::slow"},
+            test! {name: "cli::help"},
+        ];
+
+        let mut output = Vec::new();
+        let report = harness(tests)
+            .with_filter(
+                DefaultFilter::default()
+                    .with_filter(["parser::"])
+                    .with_skip(["slow"]),
+            )
+            .with_formatter(PrettyFormatter::default().with_target(&mut output))
+            .with_explain(true)
+            .list();
+        assert_eq!(report.exit_code(), ExitCode::SUCCESS);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "parser::json: test\n\
+             parser::slow: filtered out, matched skip `slow`\n\
+             cli::help: filtered out, did not match filter `parser::`\n\
+             \n1 test\n"
+        );
+
+        let filter = DefaultFilter::default()
+            .with_filter(["json", "yaml"])
+            .with_only_ignored(true);
+        let reasons: Vec<_> = filter
+            .explain(&[test! {name: "json"}, test! {name: "toml"}])
+            .into_iter()
+            .map(|exclusion| exclusion.reason)
+            .collect();
+        assert_eq!(
+            reasons,
+            ["not ignored", "did not match any filter of `json`, `yaml`"]
+        );
+    }
 }
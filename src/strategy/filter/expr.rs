@@ -0,0 +1,517 @@
+use std::{borrow::Cow, error::Error, fmt, iter, str::FromStr};
+
+use crate::{
+    filter::{FilterExclusion, FilteredTests, TestFilter},
+    pattern::{Glob, PatternError, Regex},
+    test::{Test, TestMeta, TestOrigin},
+};
+
+/// Metadata of `Extra` that a [`FilterExpr`] can select tests by.
+///
+/// Tags are matched by `tag(...)`.
+/// Fields are matched by using their key as predicate, `group(db)` matches tests with a `group`
+/// field of `db`.
+///
+/// Both default to nothing, so an empty implementation is enough for `Extra` types without
+/// selectable metadata.
+pub trait FilterMetadata {
+    /// The tags of the test.
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        iter::empty()
+    }
+
+    /// The value of the field `key`, if the test has one.
+    fn field(&self, key: &str) -> Option<Cow<'_, str>> {
+        let _ = key;
+        None
+    }
+}
+
+impl FilterMetadata for () {}
+
+/// A [`TestFilter`] selecting tests by a filter expression.
+///
+/// An expression combines predicates on the test metadata:
+///
+/// ```text
+/// name(~parser) & !tag(slow) | group(db)
+/// ```
+///
+/// The predicates are:
+/// - `name(m)` matches the test name, by default as substring
+/// - `file(m)` matches the file of a [`TestOrigin::TextFile`] origin, by default as substring
+/// - `tag(m)` matches any of the [tags](FilterMetadata::tags), by default exactly
+/// - `ignored()` matches tests marked as ignored in their metadata
+/// - `should_panic()` matches tests expected to panic
+/// - `all()` and `none()` match every and no test
+/// - any other `key(m)` matches the [field](FilterMetadata::field) `key`, by default exactly
+///
+/// A matcher `m` may choose how it matches by a prefix:
+/// `~text` matches substrings, `=text` matches exactly, `#glob` matches a [`Glob`] and
+/// `/regex/` searches for a [`Regex`].
+///
+/// Predicates are combined with `!` (or `not`), `&` (or `and`) and `|` (or `or`), in order of
+/// precedence, and grouped with parentheses.
+/// Tests that do not match the expression are filtered out.
+#[derive(Debug, Clone)]
+pub struct FilterExpr {
+    source: String,
+    expr: Expr,
+}
+
+impl FilterExpr {
+    /// Parse a filter expression.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error pointing at the column where parsing failed.
+    pub fn parse(source: &str) -> Result<Self, FilterExprError> {
+        let mut parser = Parser {
+            source,
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let expr = parser.parse_or()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error("expected `&`, `|` or the end of the expression"));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Return the expression as it was parsed.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Return whether the test matches the expression.
+    pub fn is_match<Extra: FilterMetadata>(&self, meta: &TestMeta<Extra>) -> bool {
+        self.expr.is_match(meta)
+    }
+}
+
+impl FromStr for FilterExpr {
+    type Err = FilterExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl<Extra: FilterMetadata> TestFilter<Extra> for FilterExpr {
+    fn filter<'t>(
+        &self,
+        tests: &'t [Test<Extra>],
+    ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra> {
+        let remaining: Vec<_> = tests.iter().filter(|test| self.is_match(test)).collect();
+        FilteredTests {
+            filtered_out: tests.len() - remaining.len(),
+            tests: remaining.into_iter(),
+        }
+    }
+
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        tests
+            .iter()
+            .filter(|test| !self.is_match(test))
+            .map(|test| FilterExclusion {
+                test,
+                reason: format!("did not match `{}`", self.source).into(),
+            })
+            .collect()
+    }
+}
+
+/// An error from parsing a [`FilterExpr`].
+///
+/// The [`Display`](fmt::Display) implementation shows the expression with a marker under the
+/// offending column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterExprError {
+    /// The expression that failed to parse.
+    pub source: String,
+    /// The 1-based column, counted in characters, where the error was detected.
+    pub column: usize,
+    /// What went wrong.
+    pub message: Cow<'static, str>,
+}
+
+impl fmt::Display for FilterExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} at column {}", self.message, self.column)?;
+        writeln!(f, "  {}", self.source)?;
+        write!(f, "  {:>width$}", "^", width = self.column)
+    }
+}
+
+impl Error for FilterExprError {}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    All,
+    None,
+    Name(Matcher),
+    File(Matcher),
+    Tag(Matcher),
+    Field(String, Matcher),
+    Ignored,
+    ShouldPanic,
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn is_match<Extra: FilterMetadata>(&self, meta: &TestMeta<Extra>) -> bool {
+        match self {
+            Expr::All => true,
+            Expr::None => false,
+            Expr::Name(matcher) => matcher.is_match(&meta.name),
+            Expr::File(matcher) => match &meta.origin {
+                Some(TestOrigin::TextFile { file, .. }) => matcher.is_match(file),
+                _ => false,
+            },
+            Expr::Tag(matcher) => meta.extra.tags().any(|tag| matcher.is_match(tag)),
+            Expr::Field(key, matcher) => meta
+                .extra
+                .field(key)
+                .is_some_and(|value| matcher.is_match(&value)),
+            Expr::Ignored => meta.ignore.ignored(),
+            Expr::ShouldPanic => meta.should_panic.should_panic(),
+            Expr::Not(expr) => !expr.is_match(meta),
+            Expr::And(lhs, rhs) => lhs.is_match(meta) && rhs.is_match(meta),
+            Expr::Or(lhs, rhs) => lhs.is_match(meta) || rhs.is_match(meta),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Contains(String),
+    Exact(String),
+    Glob(Glob),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Contains(part) => text.contains(part.as_str()),
+            Matcher::Exact(exact) => text == exact,
+            Matcher::Glob(glob) => glob.is_match(text),
+            Matcher::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: impl Into<Cow<'static, str>>) -> FilterExprError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<Cow<'static, str>>) -> FilterExprError {
+        FilterExprError {
+            source: self.source.to_string(),
+            column: pos + 1,
+            message: message.into(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        let found = self.peek() == Some(c);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consume `keyword` if it is the next identifier.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.ident() == keyword {
+            true => true,
+            false => {
+                self.pos = start;
+                false
+            }
+        }
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, FilterExprError> {
+        let mut expr = self.parse_and()?;
+        while self.eat('|') || self.eat_keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, FilterExprError> {
+        let mut expr = self.parse_unary()?;
+        while self.eat('&') || self.eat_keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, FilterExprError> {
+        if self.eat('!') || self.eat_keyword("not") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+
+        if self.eat('(') {
+            let expr = self.parse_or()?;
+            if !self.eat(')') {
+                return Err(self.error("expected `)`"));
+            }
+            return Ok(expr);
+        }
+
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> Result<Expr, FilterExprError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.ident();
+        if name.is_empty() {
+            return Err(match self.peek() {
+                Some(c) => self.error(format!("unexpected `{c}`, expected a predicate")),
+                None => self.error("unexpected end, expected a predicate"),
+            });
+        }
+        if !self.eat('(') {
+            return Err(self.error(format!("expected `(` after `{name}`")));
+        }
+
+        let expr = match name.as_str() {
+            "all" => Expr::All,
+            "none" => Expr::None,
+            "ignored" => Expr::Ignored,
+            "should_panic" => Expr::ShouldPanic,
+            "name" => Expr::Name(self.parse_matcher(Matcher::Contains)?),
+            "file" => Expr::File(self.parse_matcher(Matcher::Contains)?),
+            "tag" => Expr::Tag(self.parse_matcher(Matcher::Exact)?),
+            "and" | "or" | "not" => {
+                return Err(self.error_at(start, format!("unexpected `{name}`")));
+            }
+            _ => Expr::Field(name, self.parse_matcher(Matcher::Exact)?),
+        };
+
+        if !self.eat(')') {
+            return Err(self.error("expected `)`"));
+        }
+        Ok(expr)
+    }
+
+    fn parse_matcher(
+        &mut self,
+        default: fn(String) -> Matcher,
+    ) -> Result<Matcher, FilterExprError> {
+        self.skip_whitespace();
+        let kind = self.peek();
+        if matches!(kind, Some('~' | '=' | '#' | '/')) {
+            self.pos += 1;
+        }
+
+        let start = self.pos;
+        let text = match kind {
+            Some('/') => self.regex_text()?,
+            _ => self.plain_text(),
+        };
+        if text.is_empty() {
+            return Err(self.error("expected a matcher"));
+        }
+
+        let pattern_error = |err: PatternError| self.error_at(start + err.position, err.message);
+        Ok(match kind {
+            Some('~') => Matcher::Contains(text),
+            Some('=') => Matcher::Exact(text),
+            Some('#') => Matcher::Glob(Glob::new(&text).map_err(pattern_error)?),
+            Some('/') => Matcher::Regex(Regex::new(&text).map_err(pattern_error)?),
+            _ => default(text),
+        })
+    }
+
+    /// Read the text up to the closing parenthesis, `\` escapes the next character.
+    fn plain_text(&mut self) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                ')' => break,
+                '\\' if self.pos + 1 < self.chars.len() => {
+                    text.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                c => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    /// Read a regex up to the closing `/`, `\/` is an escaped slash.
+    fn regex_text(&mut self) -> Result<String, FilterExprError> {
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None => return Err(self.error("expected closing `/`")),
+                Some('/') => {
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                Some('\\') if self.chars.get(self.pos + 1) == Some(&'/') => {
+                    text.push('/');
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    #[derive(Debug, Default)]
+    struct Meta {
+        tags: &'static [&'static str],
+        group: &'static str,
+    }
+
+    impl FilterMetadata for Meta {
+        fn tags(&self) -> impl Iterator<Item = &str> {
+            self.tags.iter().copied()
+        }
+
+        fn field(&self, key: &str) -> Option<Cow<'_, str>> {
+            (key == "group").then_some(Cow::Borrowed(self.group))
+        }
+    }
+
+    fn meta(
+        name: &'static str,
+        tags: &'static [&'static str],
+        group: &'static str,
+    ) -> TestMeta<Meta> {
+        TestMeta {
+            name: name.into(),
+            ignore: Default::default(),
+            should_panic: Default::default(),
+            origin: crate::origin!(),
+            extra: Meta { tags, group },
+        }
+    }
+
+    fn select(expr: &str, tests: &[TestMeta<Meta>]) -> Vec<&'static str> {
+        let expr = FilterExpr::parse(expr).unwrap();
+        tests
+            .iter()
+            .filter(|meta| expr.is_match(meta))
+            .map(|meta| match &meta.name {
+                Cow::Borrowed(name) => *name,
+                Cow::Owned(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn expressions_select_tests() {
+        let tests = [
+            meta("parser::json", &[], "unit"),
+            meta("parser::slow_fuzz", &["slow"], "unit"),
+            meta("db::migrate", &["slow"], "db"),
+            meta("cli::help", &[], "unit"),
+        ];
+
+        assert_eq!(
+            select("name(~parser) & !tag(slow) | group(db)", &tests),
+            ["parser::json", "db::migrate"]
+        );
+        assert_eq!(
+            select("name(parser) and not (tag(slow) or group(db))", &tests),
+            ["parser::json"]
+        );
+        assert_eq!(select("name(=cli::help)", &tests), ["cli::help"]);
+        assert_eq!(select("name(#*::*_*)", &tests), ["parser::slow_fuzz"]);
+        assert_eq!(
+            select(r"name(/^(db|cli)::/)", &tests),
+            ["db::migrate", "cli::help"]
+        );
+        assert_eq!(select(&format!("file({})", file!()), &tests).len(), 4);
+        assert!(select("none() | ignored() | should_panic()", &tests).is_empty());
+        assert_eq!(select("all()", &tests).len(), 4);
+    }
+
+    #[test]
+    fn filters_tests_in_harness() {
+        let tests = &[test! {name: "keep_me"}, test! {name: "drop_me"}];
+        let report = harness(tests)
+            .with_filter(FilterExpr::parse("!name(drop)").unwrap())
+            .run();
+        assert_eq!(report.outcomes.len(), 1);
+        assert_eq!(report.outcomes[0].0, "keep_me");
+    }
+
+    #[test]
+    fn errors_point_at_column() {
+        let column = |expr: &str| FilterExpr::parse(expr).unwrap_err().column;
+        assert_eq!(column("name(x) &"), 10);
+        assert_eq!(column("name(x) tag(y)"), 9);
+        assert_eq!(column("name(x"), 7);
+        assert_eq!(column("!(name(x)"), 10);
+        assert_eq!(column("name(/(a/)"), 9);
+        assert_eq!(column("tag()"), 5);
+        assert_eq!(column("$"), 1);
+
+        let err = FilterExpr::parse("name(x) | $").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unexpected `$`, expected a predicate at column 11\n  name(x) | $\n            ^"
+        );
+    }
+}
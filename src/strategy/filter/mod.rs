@@ -15,6 +15,8 @@
 //!
 //! Implement [`TestFilter`] to define a filter strategy for kitest.
 
+use std::{borrow::Cow, mem, ptr};
+
 use crate::test::Test;
 
 mod no;
@@ -23,6 +25,15 @@ pub use no::*;
 mod default;
 pub use default::*;
 
+mod expr;
+pub use expr::*;
+
+mod combinator;
+pub use combinator::*;
+
+mod changed;
+pub use changed::*;
+
 /// The result of applying a [`TestFilter`].
 ///
 /// This contains an iterator over the tests that are included in the run,
@@ -65,8 +76,76 @@ pub trait TestFilter<Extra> {
     ///
     /// The iterator must yield references into the original `tests` slice
     /// and must have an exact size.
+    /// Combinators rely on this to map the yielded tests back to their position, and panic if a
+    /// test from somewhere else is yielded.
     fn filter<'t>(
         &self,
         tests: &'t [Test<Extra>],
     ) -> FilteredTests<'t, impl ExactSizeIterator<Item = &'t Test<Extra>>, Extra>;
+
+    /// Explain why tests are filtered out.
+    ///
+    /// Returns an exclusion for every test that [`filter`](Self::filter) filters out, in the
+    /// order of `tests`.
+    /// This is only called in explain mode, see
+    /// [`TestHarness::with_explain`](crate::TestHarness::with_explain).
+    ///
+    /// The default implementation runs the filter and gives every excluded test the reason
+    /// "filtered out".
+    /// Filters should override it to tell which of their entries excluded a test.
+    fn explain<'t>(&self, tests: &'t [Test<Extra>]) -> Vec<FilterExclusion<'t, Extra>> {
+        let kept = selection(tests, self.filter(tests).tests);
+        tests
+            .iter()
+            .zip(kept)
+            .filter(|(_, kept)| !kept)
+            .map(|(test, _)| FilterExclusion {
+                test,
+                reason: Cow::Borrowed("filtered out"),
+            })
+            .collect()
+    }
+}
+
+/// A test filtered out by a [`TestFilter`], with the reason why.
+///
+/// Returned by [`TestFilter::explain`].
+#[derive(Debug, Clone)]
+pub struct FilterExclusion<'t, Extra> {
+    /// The excluded test.
+    pub test: &'t Test<Extra>,
+
+    /// A human readable reason, like "did not match filter `foo`".
+    pub reason: Cow<'static, str>,
+}
+
+/// Mark which tests of the slice are yielded by `kept`.
+fn selection<'t, Extra: 't>(
+    tests: &'t [Test<Extra>],
+    kept: impl Iterator<Item = &'t Test<Extra>>,
+) -> Vec<bool> {
+    let mut selected = vec![false; tests.len()];
+    for test in kept {
+        selected[position(tests, test)] = true;
+    }
+    selected
+}
+
+/// Return the index of `test` in `tests`.
+///
+/// Filters yield references into the slice, so the index is recovered from the address.
+///
+/// # Panics
+///
+/// Panics if `test` does not point into `tests`, which means a filter broke the
+/// [`TestFilter::filter`] contract.
+fn position<Extra>(tests: &[Test<Extra>], test: &Test<Extra>) -> usize {
+    let test = ptr::from_ref(test);
+    let range = tests.as_ptr_range();
+    assert!(
+        range.contains(&test),
+        "test filter yielded a test that is not part of the filtered slice, \
+         filters must only yield references into the `tests` they were given"
+    );
+    (test.addr() - range.start.addr()) / mem::size_of::<Test<Extra>>()
 }
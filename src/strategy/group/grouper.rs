@@ -1,3 +1,5 @@
+use std::{fmt::Debug, marker::PhantomData};
+
 use crate::test::TestMeta;
 
 /// A strategy for assigning tests to groups.
@@ -36,3 +38,115 @@ where
         self(meta)
     }
 }
+
+/// A [`TestGrouper`] mapping the keys of another grouper.
+///
+/// Tests whose keys map to the same key end up in the same group.
+/// Since several keys may be merged into one group, the group context of the inner grouper is
+/// not forwarded.
+pub struct MapGroupKey<Grouper, Map, GroupKey, GroupCtx = ()> {
+    grouper: Grouper,
+    map: Map,
+    _key: PhantomData<fn() -> (GroupKey, GroupCtx)>,
+}
+
+impl<Grouper, Map, GroupKey, GroupCtx> MapGroupKey<Grouper, Map, GroupKey, GroupCtx> {
+    /// Map every key returned by `grouper` with `map`.
+    pub fn new(grouper: Grouper, map: Map) -> Self {
+        Self {
+            grouper,
+            map,
+            _key: PhantomData,
+        }
+    }
+}
+
+impl<Grouper: Debug, Map, GroupKey, GroupCtx> Debug
+    for MapGroupKey<Grouper, Map, GroupKey, GroupCtx>
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MapGroupKey")
+            .field("grouper", &self.grouper)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Extra, Grouper, Map, GroupKey, GroupCtx, MappedKey> TestGrouper<Extra, MappedKey>
+    for MapGroupKey<Grouper, Map, GroupKey, GroupCtx>
+where
+    Grouper: TestGrouper<Extra, GroupKey, GroupCtx>,
+    Map: FnMut(GroupKey) -> MappedKey,
+{
+    fn group(&mut self, meta: &TestMeta<Extra>) -> MappedKey {
+        (self.map)(self.grouper.group(meta))
+    }
+}
+
+/// A [`TestGrouper`] falling back to a second grouper for tests the first one has no key for.
+///
+/// The first grouper returns an `Option` of the key, `None` hands the test to the fallback.
+/// Group context is looked up in the first grouper, the fallback provides keys only.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FallbackGrouper<Grouper, Fallback> {
+    grouper: Grouper,
+    fallback: Fallback,
+}
+
+impl<Grouper, Fallback> FallbackGrouper<Grouper, Fallback> {
+    /// Group by `grouper` and by `fallback` where `grouper` returns `None`.
+    pub fn new(grouper: Grouper, fallback: Fallback) -> Self {
+        Self { grouper, fallback }
+    }
+}
+
+impl<Extra, Grouper, Fallback, GroupKey, GroupCtx> TestGrouper<Extra, GroupKey, GroupCtx>
+    for FallbackGrouper<Grouper, Fallback>
+where
+    Grouper: TestGrouper<Extra, Option<GroupKey>, GroupCtx>,
+    Fallback: TestGrouper<Extra, GroupKey>,
+    GroupKey: Clone,
+{
+    fn group(&mut self, meta: &TestMeta<Extra>) -> GroupKey {
+        match self.grouper.group(meta) {
+            Some(key) => key,
+            None => self.fallback.group(meta),
+        }
+    }
+
+    fn group_ctx(&mut self, key: &GroupKey) -> Option<GroupCtx> {
+        self.grouper.group_ctx(&Some(key.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::*;
+
+    struct Labeled;
+
+    impl TestGrouper<(), Option<&'static str>, &'static str> for Labeled {
+        fn group(&mut self, meta: &TestMeta) -> Option<&'static str> {
+            meta.name.starts_with("db").then_some("db")
+        }
+
+        fn group_ctx(&mut self, key: &Option<&'static str>) -> Option<&'static str> {
+            (*key == Some("db")).then_some("database tests")
+        }
+    }
+
+    #[test]
+    fn grouper_keys_map_and_fall_back() {
+        let tests = [test! {name: "db::query"}, test! {name: "cli::run"}];
+
+        let mut grouper = FallbackGrouper::new(Labeled, |_: &TestMeta| "other");
+        let keys: Vec<_> = tests.iter().map(|test| grouper.group(&test.meta)).collect();
+        assert_eq!(keys, ["db", "other"]);
+        assert_eq!(grouper.group_ctx(&"db"), Some("database tests"));
+        assert_eq!(grouper.group_ctx(&"other"), None);
+
+        let mut grouper = MapGroupKey::new(|meta: &TestMeta| meta.name.len(), |len| len > 8);
+        let keys: Vec<_> = tests.iter().map(|test| grouper.group(&test.meta)).collect();
+        assert_eq!(keys, [true, false]);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    ignore::{IgnoreStatus, TestIgnore},
+    test::TestMeta,
+};
+
+/// A [`TestIgnore`] asking its strategies in order and returning the first ignore decision.
+///
+/// A test runs only if every strategy lets it run.
+/// If a strategy ignores the test, later strategies are not asked and its status, including the
+/// reason, is used.
+///
+/// Usually created via [`TestIgnore::first_non_run`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FirstNonRun<A, B>(pub A, pub B);
+
+impl<A, B> FirstNonRun<A, B> {
+    pub fn new(first: A, then: B) -> Self {
+        Self(first, then)
+    }
+}
+
+impl<Extra, A, B> TestIgnore<Extra> for FirstNonRun<A, B>
+where
+    A: TestIgnore<Extra>,
+    B: TestIgnore<Extra>,
+{
+    fn ignore(&self, meta: &TestMeta<Extra>) -> IgnoreStatus {
+        match self.0.ignore(meta) {
+            IgnoreStatus::Run => self.1.ignore(meta),
+            status => status,
+        }
+    }
+}
+
+/// A [`TestIgnore`] only asked for the tests selected by a predicate.
+///
+/// Tests not selected run.
+/// This restricts a condition like [`RequireBinary`](crate::ignore::RequireBinary) to the tests
+/// that need it.
+///
+/// Usually created via [`TestIgnore::for_tests`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ForTests<Ignore, Select> {
+    pub ignore: Ignore,
+    pub select: Select,
+}
+
+impl<Ignore, Select> ForTests<Ignore, Select> {
+    pub fn new(ignore: Ignore, select: Select) -> Self {
+        Self { ignore, select }
+    }
+}
+
+impl<Extra, Ignore, Select> TestIgnore<Extra> for ForTests<Ignore, Select>
+where
+    Ignore: TestIgnore<Extra>,
+    Select: Fn(&TestMeta<Extra>) -> bool,
+{
+    fn ignore(&self, meta: &TestMeta<Extra>) -> IgnoreStatus {
+        match (self.select)(meta) {
+            true => self.ignore.ignore(meta),
+            false => IgnoreStatus::Run,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ignore::DefaultIgnore, outcome::TestStatus, test_support::*};
+
+    #[test]
+    fn first_ignore_decision_wins() {
+        let tests = &[
+            test! {name: "runs"},
+            test! {name: "marked", ignore: "marked"},
+            test! {name: "slow_marked", ignore: "marked"},
+            test! {name: "slow"},
+        ];
+        let slow = |meta: &TestMeta| match meta.name.starts_with("slow") {
+            true => IgnoreStatus::from("slow"),
+            false => IgnoreStatus::Run,
+        };
+
+        let report = harness(tests)
+            .with_ignore(DefaultIgnore::default().first_non_run(slow))
+            .run();
+        let reasons: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| match &outcome.status {
+                TestStatus::Ignored { reason } => reason.as_deref(),
+                status => {
+                    assert!(status.passed());
+                    None
+                }
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            [None, Some("marked"), Some("marked"), Some("slow")]
+        );
+    }
+}
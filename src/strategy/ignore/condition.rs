@@ -0,0 +1,286 @@
+use std::{
+    borrow::Cow,
+    env,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use crate::{
+    ignore::{IgnoreStatus, TestIgnore},
+    test::TestMeta,
+};
+
+/// A [`TestIgnore`] ignoring tests unless an environment variable is set.
+///
+/// Useful for tests that need credentials or a service address, like `DATABASE_URL`.
+/// The variable is read once, on the first test.
+#[derive(Debug, Clone)]
+pub struct RequireEnv {
+    var: Cow<'static, str>,
+    value: Option<Cow<'static, str>>,
+    status: OnceLock<IgnoreStatus>,
+}
+
+impl RequireEnv {
+    /// Ignore tests unless `var` is set, to any value.
+    pub fn new(var: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            var: var.into(),
+            value: None,
+            status: OnceLock::new(),
+        }
+    }
+
+    /// Ignore tests unless the variable is set to exactly `value`.
+    pub fn with_value(self, value: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            value: Some(value.into()),
+            status: OnceLock::new(),
+            ..self
+        }
+    }
+}
+
+impl<Extra> TestIgnore<Extra> for RequireEnv {
+    fn ignore(&self, _: &TestMeta<Extra>) -> IgnoreStatus {
+        self.status
+            .get_or_init(|| {
+                let actual = env::var_os(self.var.as_ref());
+                match (&self.value, actual) {
+                    (None, Some(_)) => IgnoreStatus::Run,
+                    (Some(value), Some(actual)) if actual == value.as_ref() => IgnoreStatus::Run,
+                    (None, None) => format!("requires environment variable `{}`", self.var).into(),
+                    (Some(value), _) => format!(
+                        "requires environment variable `{}` to be `{value}`",
+                        self.var
+                    )
+                    .into(),
+                }
+            })
+            .clone()
+    }
+}
+
+/// A [`TestIgnore`] ignoring tests unless a binary can be found on `PATH`.
+///
+/// Useful for tests driving external tools, like `docker` or `git`.
+/// A name containing a path separator is checked as path instead.
+/// On Windows, the extensions of `PATHEXT` are tried as well.
+/// The lookup happens once, on the first test.
+#[derive(Debug, Clone)]
+pub struct RequireBinary {
+    name: Cow<'static, str>,
+    status: OnceLock<IgnoreStatus>,
+}
+
+impl RequireBinary {
+    /// Ignore tests unless the binary `name` is found.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            name: name.into(),
+            status: OnceLock::new(),
+        }
+    }
+}
+
+impl<Extra> TestIgnore<Extra> for RequireBinary {
+    fn ignore(&self, _: &TestMeta<Extra>) -> IgnoreStatus {
+        self.status
+            .get_or_init(|| match find_binary(&self.name) {
+                true => IgnoreStatus::Run,
+                false => format!("requires `{}` on PATH", self.name).into(),
+            })
+            .clone()
+    }
+}
+
+fn find_binary(name: &str) -> bool {
+    if name.contains(['/', std::path::MAIN_SEPARATOR]) {
+        return Path::new(name).is_file();
+    }
+
+    let extensions: Vec<String> = match cfg!(windows) {
+        true => env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(str::to_string)
+            .collect(),
+        false => Vec::new(),
+    };
+    let Some(path) = env::var_os("PATH") else {
+        return false;
+    };
+    env::split_paths(&path).any(|dir| {
+        let candidate = dir.join(name);
+        candidate.is_file()
+            || extensions.iter().any(|ext| {
+                let mut file = candidate.clone().into_os_string();
+                file.push(ext);
+                Path::new(&file).is_file()
+            })
+    })
+}
+
+/// A [`TestIgnore`] ignoring tests unless a path exists.
+///
+/// The path may name anything, like a file, a directory or a Unix socket such as
+/// `/var/run/docker.sock`.
+/// Unlike the other conditions, this is checked for every test, so tests may create the path
+/// while the run is in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequirePath {
+    path: PathBuf,
+}
+
+impl RequirePath {
+    /// Ignore tests unless `path` exists.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl<Extra> TestIgnore<Extra> for RequirePath {
+    fn ignore(&self, _: &TestMeta<Extra>) -> IgnoreStatus {
+        match self.path.exists() {
+            true => IgnoreStatus::Run,
+            false => format!("requires `{}` to exist", self.path.display()).into(),
+        }
+    }
+}
+
+/// A [`TestIgnore`] ignoring tests on some targets.
+///
+/// Operating systems are compared against [`OS`](env::consts::OS) and
+/// [`FAMILY`](env::consts::FAMILY), so both `"macos"` and `"unix"` work.
+/// Architectures are compared against [`ARCH`](env::consts::ARCH), like `"x86_64"` or
+/// `"wasm32"`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IgnoreOnTarget {
+    os: Vec<Cow<'static, str>>,
+    arch: Vec<Cow<'static, str>>,
+}
+
+impl IgnoreOnTarget {
+    /// Ignore tests on these operating systems or families.
+    ///
+    /// This replaces the previous list.
+    pub fn with_os(self, os: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        Self {
+            os: os.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+
+    /// Ignore tests on these architectures.
+    ///
+    /// This replaces the previous list.
+    pub fn with_arch(self, arch: impl IntoIterator<Item = impl Into<Cow<'static, str>>>) -> Self {
+        Self {
+            arch: arch.into_iter().map(Into::into).collect(),
+            ..self
+        }
+    }
+}
+
+impl<Extra> TestIgnore<Extra> for IgnoreOnTarget {
+    fn ignore(&self, _: &TestMeta<Extra>) -> IgnoreStatus {
+        let os = self
+            .os
+            .iter()
+            .find(|os| *os == env::consts::OS || *os == env::consts::FAMILY);
+        if let Some(os) = os {
+            return format!("not supported on `{os}`").into();
+        }
+        match self.arch.iter().find(|arch| *arch == env::consts::ARCH) {
+            Some(arch) => format!("not supported on `{arch}`").into(),
+            None => IgnoreStatus::Run,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ignore::DefaultIgnore, outcome::TestStatus, test_support::*};
+
+    fn reason(ignore: impl TestIgnore<()>) -> Option<String> {
+        match ignore.ignore(&TestMeta::default()) {
+            IgnoreStatus::Run => None,
+            IgnoreStatus::Ignore => Some(String::new()),
+            IgnoreStatus::IgnoreWithReason(reason) => Some(reason.into_owned()),
+        }
+    }
+
+    #[test]
+    fn conditions_give_reasons() {
+        const MISSING: &str = "KITEST_SURELY_NOT_SET";
+
+        assert_eq!(reason(RequireEnv::new("PATH")), None);
+        assert_eq!(
+            reason(RequireEnv::new(MISSING)).unwrap(),
+            "requires environment variable `KITEST_SURELY_NOT_SET`"
+        );
+        assert_eq!(
+            reason(RequireEnv::new("PATH").with_value("nope")).unwrap(),
+            "requires environment variable `PATH` to be `nope`"
+        );
+
+        let exe = env::current_exe().unwrap();
+        assert_eq!(reason(RequireBinary::new(exe.display().to_string())), None);
+        assert_eq!(
+            reason(RequireBinary::new("kitest-surely-not-installed")).unwrap(),
+            "requires `kitest-surely-not-installed` on PATH"
+        );
+
+        assert_eq!(reason(RequirePath::new(file!())), None);
+        assert_eq!(
+            reason(RequirePath::new("surely/not/here.sock")).unwrap(),
+            "requires `surely/not/here.sock` to exist"
+        );
+
+        assert_eq!(reason(IgnoreOnTarget::default()), None);
+        assert_eq!(
+            reason(IgnoreOnTarget::default().with_os([env::consts::OS])).unwrap(),
+            format!("not supported on `{}`", env::consts::OS)
+        );
+        assert_eq!(
+            reason(IgnoreOnTarget::default().with_arch(["not-an-arch", env::consts::ARCH]))
+                .unwrap(),
+            format!("not supported on `{}`", env::consts::ARCH)
+        );
+    }
+
+    #[test]
+    fn conditions_compose_with_meta_ignore() {
+        let tests = &[
+            test! {name: "db::query"},
+            test! {name: "db::slow", ignore: "too slow"},
+            test! {name: "unit"},
+        ];
+
+        let report = harness(tests)
+            .with_ignore(
+                DefaultIgnore::default().first_non_run(
+                    RequireEnv::new("KITEST_SURELY_NOT_SET")
+                        .for_tests(|meta: &TestMeta| meta.name.starts_with("db::")),
+                ),
+            )
+            .run();
+        let reasons: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| match &outcome.status {
+                TestStatus::Ignored { reason } => reason.as_deref(),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                Some("requires environment variable `KITEST_SURELY_NOT_SET`"),
+                Some("too slow"),
+                None
+            ]
+        );
+    }
+}
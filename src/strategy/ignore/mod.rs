@@ -9,6 +9,20 @@
 //! right before each test would run, so the decision can be made during the run.
 //!
 //! Implement [`TestIgnore`] to define an ignore strategy for kitest.
+//!
+//! Ready-made conditions like [`RequireEnv`], [`RequireBinary`], [`RequirePath`] and
+//! [`IgnoreOnTarget`] ignore tests with a reason when the environment does not fit.
+//! Restrict them to some tests with [`TestIgnore::for_tests`] and combine them with the ignore
+//! flag of the test metadata via [`TestIgnore::first_non_run`]:
+//!
+//! ```
+//! use kitest::{ignore::*, test::TestMeta};
+//!
+//! let ignore = DefaultIgnore::default().first_non_run(
+//!     RequireBinary::new("docker").for_tests(|meta: &TestMeta| meta.name.starts_with("docker::")),
+//! );
+//! # let _: &dyn TestIgnore<()> = &ignore;
+//! ```
 
 use std::borrow::Cow;
 
@@ -20,6 +34,12 @@ pub use no::*;
 mod default;
 pub use default::*;
 
+mod combinator;
+pub use combinator::*;
+
+mod condition;
+pub use condition::*;
+
 /// The ignore decision for a single test.
 ///
 /// The harness calls the ignore strategy right before a test would execute.
@@ -81,6 +101,28 @@ pub trait TestIgnore<Extra> {
     /// Returning [`IgnoreStatus::Run`] runs the test. Returning an ignore status
     /// skips execution and marks the test as ignored in the report.
     fn ignore(&self, meta: &TestMeta<Extra>) -> IgnoreStatus;
+
+    /// Combine with another strategy that is asked if this one lets the test run.
+    ///
+    /// See [`FirstNonRun`].
+    fn first_non_run<Then>(self, then: Then) -> FirstNonRun<Self, Then>
+    where
+        Self: Sized,
+        Then: TestIgnore<Extra>,
+    {
+        FirstNonRun(self, then)
+    }
+
+    /// Only ask this strategy for the tests `select` returns `true` for.
+    ///
+    /// See [`ForTests`].
+    fn for_tests<Select>(self, select: Select) -> ForTests<Self, Select>
+    where
+        Self: Sized,
+        Select: Fn(&TestMeta<Extra>) -> bool,
+    {
+        ForTests::new(self, select)
+    }
 }
 
 impl<Extra, F> TestIgnore<Extra> for F
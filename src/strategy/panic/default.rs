@@ -4,9 +4,10 @@ use std::{
 };
 
 use crate::{
+    capture::TEST_PANIC_INFO,
     outcome::{TestFailure, TestStatus},
     panic::{PanicExpectation, TestPanicHandler},
-    test::{TestMeta, TestResult},
+    test::{Skipped, TestMeta, TestResult},
 };
 
 /// The default [`TestPanicHandler`] implementation used by the default test harness.
@@ -19,6 +20,14 @@ use crate::{
 /// - [`PanicExpectation::ShouldPanic`] treats a panic as a pass and a normal return as a failure
 /// - [`PanicExpectation::ShouldPanicWithExpected`] additionally requires the panic message
 ///   to contain the expected text fragment
+/// - the other variants additionally require the panic to satisfy their rule, see
+///   [`PanicExpectation::check`]
+///
+/// A panic that does not satisfy the expectation fails with [`TestFailure::PanicMismatch`],
+/// naming the failed rule.
+///
+/// Tests skipping themselves via [`skip!`](crate::skip) are reported as
+/// [`TestStatus::Ignored`], regardless of the expectation, see [`Skipped::report`].
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct DefaultPanicHandler;
 
@@ -35,30 +44,44 @@ impl DefaultPanicHandler {
     }
 }
 
+fn payload_as_str(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
 impl<Extra> TestPanicHandler<Extra> for DefaultPanicHandler {
     fn handle<F: FnOnce() -> TestResult>(&self, f: F, meta: &TestMeta<Extra>) -> TestStatus {
         // we are very lenient here, shared state is an issue anyway in test runners,
         // so unwinding should cause larger issues than that
         let result = catch_unwind(AssertUnwindSafe(f));
-        TestStatus::Failed(match (result, &meta.should_panic) {
-            (Ok(result), PanicExpectation::ShouldNotPanic) => return result.into(),
-            (Ok(_), PanicExpectation::ShouldPanic) => TestFailure::DidNotPanic { expected: None },
-            (Ok(_), PanicExpectation::ShouldPanicWithExpected(expected)) => {
-                TestFailure::DidNotPanic {
-                    expected: Some(expected.to_string()),
-                }
+        let expectation: &PanicExpectation = &meta.should_panic;
+        TestStatus::Failed(match result {
+            Err(err) if err.is::<Skipped>() => {
+                let skipped = err.downcast::<Skipped>().expect("checked type");
+                return skipped.report();
             }
-            (Err(err), PanicExpectation::ShouldNotPanic) => {
-                TestFailure::Panicked(Self::payload_as_string(err))
-            }
-            (Err(_), PanicExpectation::ShouldPanic) => return TestStatus::Passed,
-            (Err(err), PanicExpectation::ShouldPanicWithExpected(expected)) => {
-                let msg = Self::payload_as_string(err);
-                match msg.contains(expected.as_ref()) {
-                    true => return TestStatus::Passed,
-                    false => TestFailure::PanicMismatch {
-                        got: msg,
-                        expected: Some(expected.to_string()),
+            Ok(result) => match TestStatus::from(result) {
+                status if status.ignored() || !expectation.should_panic() => return status,
+                _ => TestFailure::DidNotPanic {
+                    expected: expectation.expected().map(String::from),
+                },
+            },
+            Err(err) if !expectation.should_panic() => TestFailure::Panicked {
+                message: Self::payload_as_string(err),
+                location: TEST_PANIC_INFO
+                    .with_borrow(|info| info.as_ref().and_then(|info| info.location.clone())),
+            },
+            Err(err) => {
+                let msg = payload_as_str(&*err);
+                match expectation.check(&*err, msg) {
+                    Ok(()) => return TestStatus::Passed,
+                    Err(rule) => TestFailure::PanicMismatch {
+                        got: msg.to_string(),
+                        expected: expectation.expected().map(String::from),
+                        rule,
                     },
                 }
             }
@@ -68,8 +91,15 @@ impl<Extra> TestPanicHandler<Extra> for DefaultPanicHandler {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+
     use super::*;
-    use crate::{runner::SimpleRunner, test_support::*};
+    use crate::{
+        capture::{PanicInfo, PanicLocation},
+        panic::{PanicPredicate, PanicRule},
+        runner::SimpleRunner,
+        test_support::*,
+    };
 
     #[test]
     fn handling_panics_works() {
@@ -113,4 +143,216 @@ mod tests {
         assert!(outcomes[3].1.failed());
         assert!(outcomes[4].1.failed());
     }
+
+    #[test]
+    fn panics_are_recorded_with_location() {
+        let line = line!() + 3;
+        let tests = &[test! {
+            name: "panic",
+            func: || if true { panic!("oh no") }
+        }];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+        let outcome = &report.outcomes[0].1;
+
+        let TestStatus::Failed(TestFailure::Panicked { message, location }) = &outcome.status
+        else {
+            panic!("expected a panic, got {:?}", outcome.status);
+        };
+        assert_eq!(message, "oh no");
+        let location = location
+            .as_ref()
+            .expect("default hook records the location");
+        assert_eq!(location.file, file!());
+        assert_eq!(location.line, line);
+
+        let info = outcome.attachments.get::<PanicInfo>().unwrap();
+        assert_eq!(info.location.as_ref(), Some(location));
+        assert_eq!(info.payload, "oh no");
+        assert_eq!(info.thread.as_deref(), std::thread::current().name());
+        assert!(TEST_PANIC_INFO.with_borrow(Option::is_none));
+    }
+
+    #[test]
+    fn panic_info_is_only_kept_for_the_current_panic() {
+        let tests = &[
+            test! {name: "resumed", func: || -> () {
+                std::panic::resume_unwind(Box::new("resumed"))
+            }},
+            test! {name: "caught", func: || {
+                let _ = std::panic::catch_unwind(|| panic!("caught"));
+            }},
+        ];
+
+        TEST_PANIC_INFO.set(Some(PanicInfo {
+            location: Some(PanicLocation {
+                file: "stale.rs".into(),
+                line: 1,
+                column: 1,
+            }),
+            thread: None,
+            payload: "stale".into(),
+            backtrace: None,
+        }));
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+
+        let caught = &report.outcomes[1].1;
+        assert!(caught.passed());
+        assert!(caught.attachments.get::<PanicInfo>().is_none());
+        let resumed = &report.outcomes[0].1;
+        assert!(matches!(
+            resumed.status,
+            TestStatus::Failed(TestFailure::Panicked { location: None, .. })
+        ));
+        assert!(resumed.attachments.get::<PanicInfo>().is_none());
+    }
+
+    fn requires_docker() {
+        crate::skip!("no {} available", "docker");
+    }
+
+    #[test]
+    fn tests_can_skip_at_runtime() {
+        let tests = &[
+            test! {name: "macro", func: requires_docker},
+            test! {name: "macro_no_reason", should_panic: true, func: || if true { crate::skip!() }},
+            test! {name: "result", func: || TestResult::skipped("not today")},
+            test! {
+                name: "result_should_panic",
+                should_panic: true,
+                func: || TestResult::skipped("not now")
+            },
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+        let reasons: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|(_, outcome)| match &outcome.status {
+                TestStatus::Ignored { reason } if outcome.skipped() => reason.as_deref(),
+                status => panic!("expected a skip, got {status:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            reasons,
+            [
+                Some("no docker available"),
+                None,
+                Some("not today"),
+                Some("not now")
+            ]
+        );
+        assert!(report.outcomes[0].1.output.raw().is_empty());
+    }
+
+    #[test]
+    fn panic_expectation_rules() {
+        #[derive(Debug)]
+        struct MyError;
+
+        // patterns are plain strings, so expectations work in `const` test declarations
+        const GLOB: PanicExpectation =
+            PanicExpectation::ShouldPanicMatchingGlob(Cow::Borrowed("index * out of*"));
+
+        let tests = &[
+            test! {
+                name: "exact",
+                should_panic: PanicExpectation::ShouldPanicWithExact("oh no".into()),
+                func: || if true { panic!("oh no") }
+            },
+            test! {
+                name: "exact_mismatch",
+                should_panic: PanicExpectation::ShouldPanicWithExact("oh".into()),
+                func: || if true { panic!("oh no") }
+            },
+            test! {
+                name: "glob",
+                should_panic: GLOB,
+                func: || if true { panic!("index 3 out of range") }
+            },
+            test! {
+                name: "regex",
+                should_panic: PanicExpectation::ShouldPanicMatchingRegex(r"\d+ out".into()),
+                func: || if true { panic!("index 3 out of range") }
+            },
+            test! {
+                name: "regex_mismatch",
+                should_panic: PanicExpectation::ShouldPanicMatchingRegex(r"^\d+ out".into()),
+                func: || if true { panic!("index 3 out of range") }
+            },
+            test! {
+                name: "invalid_regex",
+                should_panic: PanicExpectation::ShouldPanicMatchingRegex("(".into()),
+                func: || if true { panic!("(") }
+            },
+            test! {
+                name: "payload",
+                should_panic: PanicPredicate::payload_type::<MyError>(),
+                func: || if true { std::panic::panic_any(MyError) }
+            },
+            test! {
+                name: "payload_mismatch",
+                should_panic: PanicPredicate::payload_type::<MyError>(),
+                func: || if true { panic!("MyError") }
+            },
+            test! {
+                name: "did_not_panic",
+                should_panic: PanicExpectation::ShouldPanicMatchingGlob("*".into()),
+                func: || ()
+            },
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+        let statuses: Vec<_> = report
+            .outcomes
+            .into_iter()
+            .map(|(_, outcome)| outcome.status)
+            .collect();
+
+        let mismatch = |status: &TestStatus| match status {
+            TestStatus::Failed(TestFailure::PanicMismatch { rule, .. }) => Some(rule.clone()),
+            _ => None,
+        };
+
+        assert!(statuses[0].passed());
+        assert_eq!(mismatch(&statuses[1]), Some(PanicRule::Exact));
+        assert!(statuses[2].passed());
+        assert!(statuses[3].passed());
+        assert_eq!(mismatch(&statuses[4]), Some(PanicRule::Regex));
+        assert!(matches!(
+            mismatch(&statuses[5]),
+            Some(PanicRule::InvalidPattern(_))
+        ));
+        assert!(statuses[6].passed());
+        assert_eq!(
+            statuses[7],
+            TestStatus::Failed(TestFailure::PanicMismatch {
+                got: String::from("MyError"),
+                expected: Some(format!(
+                    "payload of type `{}`",
+                    std::any::type_name::<MyError>()
+                )),
+                rule: PanicRule::Predicate,
+            })
+        );
+        assert_eq!(
+            statuses[8],
+            TestStatus::Failed(TestFailure::DidNotPanic {
+                expected: Some(String::from("*"))
+            })
+        );
+    }
 }
@@ -14,10 +14,16 @@
 //! Implement [`TestPanicHandler`] to define how kitest executes tests and turns
 //! panics into statuses.
 
-use std::borrow::Cow;
+use std::{
+    any::{Any, type_name},
+    borrow::Cow,
+    fmt::{self, Display},
+    ptr,
+};
 
 use crate::{
     outcome::TestStatus,
+    pattern::{Glob, Regex},
     test::{TestMeta, TestResult},
 };
 
@@ -27,6 +33,12 @@ pub use no::*;
 mod default;
 pub use default::*;
 
+mod xfail;
+pub use xfail::*;
+
+mod quarantine;
+pub use quarantine::*;
+
 /// The panic expectation for a test.
 ///
 /// This value is stored in [`TestMeta`] and can be used by a panic handler to
@@ -42,6 +54,184 @@ pub enum PanicExpectation {
 
     /// The test should panic and include an expected message.
     ShouldPanicWithExpected(Cow<'static, str>),
+
+    /// The test should panic with exactly this message.
+    ShouldPanicWithExact(Cow<'static, str>),
+
+    /// The test should panic with a message matching this [`Glob`] pattern.
+    ///
+    /// The pattern is parsed when the panic is checked.
+    ShouldPanicMatchingGlob(Cow<'static, str>),
+
+    /// The test should panic with a message containing a match of this [`Regex`].
+    ///
+    /// The expression is parsed when the panic is checked.
+    ShouldPanicMatchingRegex(Cow<'static, str>),
+
+    /// The test should panic with a payload accepted by a predicate.
+    ///
+    /// Use this to check payloads that are not strings, for example values passed to
+    /// [`std::panic::panic_any`].
+    ShouldPanicWith(PanicPredicate),
+}
+
+impl PanicExpectation {
+    /// Return `true` if the test is expected to panic.
+    pub fn should_panic(&self) -> bool {
+        !matches!(self, Self::ShouldNotPanic)
+    }
+
+    /// Return the expected message, pattern or predicate description, if any.
+    pub fn expected(&self) -> Option<&str> {
+        match self {
+            Self::ShouldNotPanic | Self::ShouldPanic => None,
+            Self::ShouldPanicWithExpected(expected)
+            | Self::ShouldPanicWithExact(expected)
+            | Self::ShouldPanicMatchingGlob(expected)
+            | Self::ShouldPanicMatchingRegex(expected) => Some(expected),
+            Self::ShouldPanicWith(predicate) => Some(&predicate.description),
+        }
+    }
+
+    /// Check a panic against this expectation.
+    ///
+    /// `message` is the panic payload formatted as text, see
+    /// [`DefaultPanicHandler::payload_as_string`].
+    /// Returns the rule the panic did not satisfy, [`ShouldNotPanic`](Self::ShouldNotPanic)
+    /// rejects every panic with [`PanicRule::NoPanic`].
+    pub fn check(&self, payload: &(dyn Any + Send), message: &str) -> Result<(), PanicRule> {
+        let (matched, rule) = match self {
+            Self::ShouldNotPanic => (false, PanicRule::NoPanic),
+            Self::ShouldPanic => (true, PanicRule::Panic),
+            Self::ShouldPanicWithExpected(expected) => {
+                (message.contains(expected.as_ref()), PanicRule::Contains)
+            }
+            Self::ShouldPanicWithExact(expected) => (message == expected, PanicRule::Exact),
+            Self::ShouldPanicMatchingGlob(pattern) => match Glob::new(pattern) {
+                Ok(glob) => (glob.is_match(message), PanicRule::Glob),
+                Err(err) => (false, PanicRule::InvalidPattern(err.to_string())),
+            },
+            Self::ShouldPanicMatchingRegex(pattern) => match Regex::new(pattern) {
+                Ok(regex) => (regex.is_match(message), PanicRule::Regex),
+                Err(err) => (false, PanicRule::InvalidPattern(err.to_string())),
+            },
+            Self::ShouldPanicWith(predicate) => ((predicate.check)(payload), PanicRule::Predicate),
+        };
+
+        match matched {
+            true => Ok(()),
+            false => Err(rule),
+        }
+    }
+}
+
+/// A predicate on the panic payload, see [`PanicExpectation::ShouldPanicWith`].
+///
+/// The fields are public, so predicates can be built in `const` contexts using a function
+/// pointer or a non capturing closure.
+#[derive(Debug, Clone, Eq)]
+pub struct PanicPredicate {
+    /// Describes what the predicate expects, shown if it rejects a panic.
+    pub description: Cow<'static, str>,
+
+    /// Returns `true` if the payload meets the expectation.
+    pub check: fn(&(dyn Any + Send)) -> bool,
+}
+
+impl PanicPredicate {
+    /// Create a predicate from a description and a check.
+    pub fn new(
+        description: impl Into<Cow<'static, str>>,
+        check: fn(&(dyn Any + Send)) -> bool,
+    ) -> Self {
+        Self {
+            description: description.into(),
+            check,
+        }
+    }
+
+    /// Expect the payload to be of type `T`.
+    pub fn payload_type<T: Any>() -> Self {
+        Self {
+            description: format!("payload of type `{}`", type_name::<T>()).into(),
+            check: |payload| payload.is::<T>(),
+        }
+    }
+}
+
+impl PartialEq for PanicPredicate {
+    fn eq(&self, other: &Self) -> bool {
+        self.description == other.description && ptr::fn_addr_eq(self.check, other.check)
+    }
+}
+
+impl From<PanicPredicate> for PanicExpectation {
+    fn from(value: PanicPredicate) -> Self {
+        Self::ShouldPanicWith(value)
+    }
+}
+
+/// The rule of a [`PanicExpectation`] that a panic did not satisfy.
+///
+/// This is carried by [`TestFailure::PanicMismatch`](crate::outcome::TestFailure::PanicMismatch).
+/// The [`Display`] implementation describes the failed rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PanicRule {
+    /// The test should not have panicked.
+    NoPanic,
+
+    /// The test should have panicked, which it did.
+    ///
+    /// This is never a reason for a mismatch, it exists to keep [`PanicExpectation::check`]
+    /// total.
+    Panic,
+
+    /// The message did not contain the expected substring.
+    Contains,
+
+    /// The message was not exactly the expected one.
+    Exact,
+
+    /// The message did not match the glob pattern.
+    Glob,
+
+    /// The message did not contain a match of the regular expression.
+    Regex,
+
+    /// The expected pattern could not be parsed.
+    InvalidPattern(String),
+
+    /// The payload was rejected by the predicate.
+    Predicate,
+}
+
+impl PanicRule {
+    /// A short label for the expected value, like `expected substring`.
+    pub fn expected_label(&self) -> &'static str {
+        match self {
+            Self::NoPanic | Self::Panic | Self::Predicate => "expected",
+            Self::Contains => "expected substring",
+            Self::Exact => "expected message",
+            Self::Glob => "expected glob",
+            Self::Regex => "expected regex",
+            Self::InvalidPattern(_) => "expected pattern",
+        }
+    }
+}
+
+impl Display for PanicRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPanic => f.write_str("test should not panic"),
+            Self::Panic => f.write_str("test should panic"),
+            Self::Contains => f.write_str("panic did not contain expected string"),
+            Self::Exact => f.write_str("panic did not match expected message exactly"),
+            Self::Glob => f.write_str("panic did not match expected glob pattern"),
+            Self::Regex => f.write_str("panic did not match expected regex"),
+            Self::InvalidPattern(err) => write!(f, "expected pattern is invalid: {err}"),
+            Self::Predicate => f.write_str("panic payload did not satisfy expectation"),
+        }
+    }
 }
 
 impl From<bool> for PanicExpectation {
@@ -0,0 +1,137 @@
+use std::{
+    collections::HashSet,
+    fs,
+    io::{self, BufRead},
+    path::Path,
+};
+
+use crate::{
+    outcome::TestStatus,
+    panic::TestPanicHandler,
+    test::{TestMeta, TestResult},
+};
+
+/// A list of quarantined test names, used by [`QuarantinePanicHandler`].
+///
+/// Names are compared exactly against [`TestMeta::name`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QuarantineList {
+    names: HashSet<String>,
+}
+
+impl QuarantineList {
+    /// Create a list from test names.
+    pub fn new(names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Read test names from a reader, one per line.
+    ///
+    /// Surrounding whitespace and empty lines are ignored, lines starting with `#` are comments.
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let lines = reader.lines().collect::<io::Result<Vec<_>>>()?;
+        Ok(Self::parse(lines.iter().map(String::as_str)))
+    }
+
+    /// Read test names from a file, one per line.
+    ///
+    /// See [`from_reader`](Self::from_reader).
+    pub fn from_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Ok(Self::parse(content.lines()))
+    }
+
+    fn parse<'l>(lines: impl Iterator<Item = &'l str>) -> Self {
+        Self::new(
+            lines
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    /// Return whether the test `name` is quarantined.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+}
+
+/// A [`TestPanicHandler`] that quarantines the tests of a [`QuarantineList`].
+///
+/// It wraps another panic handler, quarantined tests still run through it.
+/// For quarantined tests the status of the wrapped handler is replaced:
+/// - [`TestStatus::Failed`] becomes [`TestStatus::Quarantined`] with the failure, which counts
+///   as good
+/// - [`TestStatus::Passed`] becomes [`TestStatus::Quarantined`] without a failure, formatters
+///   report these so the test gets removed from the quarantine
+///
+/// Tests that are not quarantined keep the status of the wrapped handler.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QuarantinePanicHandler<PanicHandler> {
+    panic_handler: PanicHandler,
+    list: QuarantineList,
+}
+
+impl<PanicHandler> QuarantinePanicHandler<PanicHandler> {
+    /// Wrap `panic_handler`, quarantining the tests of `list`.
+    pub fn new(panic_handler: PanicHandler, list: QuarantineList) -> Self {
+        Self {
+            panic_handler,
+            list,
+        }
+    }
+}
+
+impl<Extra, PanicHandler> TestPanicHandler<Extra> for QuarantinePanicHandler<PanicHandler>
+where
+    PanicHandler: TestPanicHandler<Extra>,
+{
+    fn handle<T: FnOnce() -> TestResult>(&self, f: T, meta: &TestMeta<Extra>) -> TestStatus {
+        let status = self.panic_handler.handle(f, meta);
+        if !self.list.contains(&meta.name) {
+            return status;
+        }
+
+        match status {
+            TestStatus::Failed(failure) => TestStatus::Quarantined {
+                failure: Some(failure),
+            },
+            TestStatus::Passed => TestStatus::Quarantined { failure: None },
+            status => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::ExitCode;
+
+    use super::*;
+    use crate::{panic::DefaultPanicHandler, runner::SimpleRunner, test_support::*};
+
+    #[test]
+    fn quarantined_failures_do_not_fail() {
+        let tests = &[
+            test! {name: "flaky", func: || if true { panic!("flaked") }},
+            test! {name: "fixed", func: || ()},
+            test! {name: "pass", func: || ()},
+        ];
+
+        let list = QuarantineList::from_reader("# known flaky\n flaky \n\nfixed\n".as_bytes());
+        let list = list.unwrap();
+        assert!(list.contains("flaky") && !list.contains("# known flaky"));
+
+        let report = harness(tests)
+            .with_panic_handler(QuarantinePanicHandler::new(DefaultPanicHandler, list))
+            .with_runner(SimpleRunner::default())
+            .run();
+        let outcomes = &report.outcomes;
+
+        assert!(outcomes[0].1.quarantined());
+        assert!(outcomes[0].1.is_good());
+        assert!(outcomes[1].1.quarantined_pass());
+        assert!(outcomes[2].1.passed());
+        assert!(report.exit_code() == ExitCode::SUCCESS);
+    }
+}
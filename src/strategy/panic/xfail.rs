@@ -0,0 +1,96 @@
+use crate::{
+    outcome::{TestFailure, TestStatus},
+    panic::TestPanicHandler,
+    test::{TestMeta, TestResult},
+};
+
+/// A [`TestPanicHandler`] that supports tests which are expected to fail.
+///
+/// It wraps another panic handler and asks a function whether a test is marked as an expected
+/// failure.
+/// The function receives the [`TestMeta`], so the marker can be read from anywhere, usually from
+/// the `Extra` metadata.
+///
+/// For marked tests the status of the wrapped handler is flipped:
+/// - [`TestStatus::Failed`] becomes [`TestStatus::ExpectedFailure`], which counts as good
+/// - [`TestStatus::Passed`] and [`TestStatus::Other`] become a failure with
+///   [`TestFailure::UnexpectedPass`], so the marker gets removed once the test is fixed
+///
+/// Tests that are not marked keep the status of the wrapped handler.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct XFailPanicHandler<PanicHandler, F> {
+    panic_handler: PanicHandler,
+    is_xfail: F,
+}
+
+impl<PanicHandler, F> XFailPanicHandler<PanicHandler, F> {
+    /// Wrap `panic_handler`, treating tests as expected failures for which `is_xfail` returns
+    /// `true`.
+    pub fn new<Extra>(panic_handler: PanicHandler, is_xfail: F) -> Self
+    where
+        F: Fn(&TestMeta<Extra>) -> bool,
+    {
+        Self {
+            panic_handler,
+            is_xfail,
+        }
+    }
+}
+
+impl<Extra, PanicHandler, F> TestPanicHandler<Extra> for XFailPanicHandler<PanicHandler, F>
+where
+    PanicHandler: TestPanicHandler<Extra>,
+    F: Fn(&TestMeta<Extra>) -> bool,
+{
+    fn handle<T: FnOnce() -> TestResult>(&self, f: T, meta: &TestMeta<Extra>) -> TestStatus {
+        let status = self.panic_handler.handle(f, meta);
+        if !(self.is_xfail)(meta) {
+            return status;
+        }
+
+        match status {
+            TestStatus::Failed(TestFailure::UnexpectedPass) => status,
+            TestStatus::Failed(failure) => TestStatus::ExpectedFailure(failure),
+            TestStatus::Passed | TestStatus::Other(_) => {
+                TestStatus::Failed(TestFailure::UnexpectedPass)
+            }
+            status => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{panic::DefaultPanicHandler, runner::SimpleRunner, test_support::*};
+
+    #[test]
+    fn expected_failures_flip_status() {
+        let tests = &[
+            test! {name: "xfail_panic", func: || if true { panic!("known bug") }},
+            test! {name: "xfail_pass", func: || ()},
+            test! {name: "pass", func: || ()},
+            test! {name: "panic", func: || if true { panic!() }},
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(XFailPanicHandler::new(
+                DefaultPanicHandler,
+                |meta: &TestMeta| meta.name.starts_with("xfail"),
+            ))
+            .with_runner(SimpleRunner::default())
+            .run();
+        let outcomes = &report.outcomes;
+
+        assert!(matches!(
+            &outcomes[0].1.status,
+            TestStatus::ExpectedFailure(TestFailure::Panicked { message, .. })
+                if message == "known bug"
+        ));
+        assert!(outcomes[0].1.is_good());
+        assert!(outcomes[1].1.unexpected_pass());
+        assert!(outcomes[1].1.is_bad());
+        assert!(outcomes[2].1.passed());
+        assert!(outcomes[3].1.failed());
+    }
+}
@@ -2,6 +2,7 @@ use std::{
     cmp,
     fmt::Debug,
     num::NonZeroUsize,
+    panic,
     sync::Arc,
     thread::{self, Scope, ScopedJoinHandle},
     time::Instant,
@@ -12,10 +13,10 @@ use crate::{
         CapturePanicHookGuard, DefaultPanicHookProvider, OutputCapture, PanicHook,
         PanicHookProvider, TEST_OUTPUT_CAPTURE,
     },
-    outcome::{TestOutcome, TestOutcomeAttachments, TestStatus},
+    outcome::{TestOutcome, TestStatus},
     runner::{
-        TestRunner,
-        scope::{NoScopeFactory, TestScope, TestScopeFactory},
+        TestRunner, attachments,
+        scope::{NoScopeFactory, NoWorkerScope, TestScope, TestScopeFactory, WorkerScope},
     },
     test::TestMeta,
 };
@@ -31,24 +32,32 @@ use crate::{
 /// This runner uses multiple threads.
 /// By default, the thread count is based on [`std::thread::available_parallelism`], but it can be
 /// overridden.
+/// The stack size and names of the spawned threads can be configured as well, and each test can
+/// optionally get a [fresh thread](Self::with_fresh_thread_per_test).
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct DefaultRunner<PanicHookProvider, TestScopeFactory> {
+pub struct DefaultRunner<PanicHookProvider, TestScopeFactory, WorkerScope = NoWorkerScope> {
     threads: NonZeroUsize,
+    thread_options: ThreadOptions,
     panic_hook_provider: PanicHookProvider,
     test_scope_factory: Arc<TestScopeFactory>,
+    worker_scope: Arc<WorkerScope>,
 }
 
 impl Default for DefaultRunner<DefaultPanicHookProvider, NoScopeFactory> {
     fn default() -> Self {
         Self {
             threads: std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN),
+            thread_options: ThreadOptions::default(),
             panic_hook_provider: DefaultPanicHookProvider,
             test_scope_factory: Arc::new(NoScopeFactory),
+            worker_scope: Arc::new(NoWorkerScope),
         }
     }
 }
 
-impl<PanicHookProvider, TestScopeFactory> DefaultRunner<PanicHookProvider, TestScopeFactory> {
+impl<PanicHookProvider, TestScopeFactory, WorkerScope>
+    DefaultRunner<PanicHookProvider, TestScopeFactory, WorkerScope>
+{
     /// Create a default runner using the default panic hook provider.
     ///
     /// This is the same as `DefaultRunner::default()`.
@@ -66,6 +75,39 @@ impl<PanicHookProvider, TestScopeFactory> DefaultRunner<PanicHookProvider, TestS
         }
     }
 
+    /// Set the stack size in bytes of the threads executing tests.
+    ///
+    /// By default, threads are spawned with the default stack size of [`std::thread`].
+    /// Increase this if tests recurse deeply or keep large values on the stack.
+    pub fn with_stack_size(mut self, size: usize) -> Self {
+        self.thread_options.stack_size = Some(size);
+        self
+    }
+
+    /// Set the name template for the threads executing tests.
+    ///
+    /// Every occurrence of `{idx}` is replaced with the index of the worker.
+    /// The default template is `kitest-worker-{idx}`.
+    /// The thread name shows up in panic messages, so keep it recognizable.
+    pub fn with_thread_name(mut self, template: impl Into<String>) -> Self {
+        self.thread_options.name = template.into();
+        self
+    }
+
+    /// Execute every test on a freshly spawned thread.
+    ///
+    /// By default, each worker thread executes many tests one after another, so thread locals
+    /// set by one test are visible to the next test on the same worker.
+    /// With this enabled, each worker spawns a new thread for every test and waits for it to
+    /// finish, similar to the built in Rust test harness.
+    /// The number of tests executing at the same time is still bounded by the thread count.
+    ///
+    /// As every test thread is a new worker, the [`WorkerScope`] hooks run once per test.
+    pub fn with_fresh_thread_per_test(mut self, enabled: bool) -> Self {
+        self.thread_options.fresh_thread_per_test = enabled;
+        self
+    }
+
     /// Replace the panic hook provider used for output capture.
     ///
     /// The runner is generic over a [`PanicHookProvider`] so we can swap out the
@@ -73,11 +115,13 @@ impl<PanicHookProvider, TestScopeFactory> DefaultRunner<PanicHookProvider, TestS
     pub fn with_panic_hook_provider<WithPanicHookProvider>(
         self,
         panic_hook_provider: WithPanicHookProvider,
-    ) -> DefaultRunner<WithPanicHookProvider, TestScopeFactory> {
+    ) -> DefaultRunner<WithPanicHookProvider, TestScopeFactory, WorkerScope> {
         DefaultRunner {
             threads: self.threads,
+            thread_options: self.thread_options,
             panic_hook_provider,
             test_scope_factory: self.test_scope_factory,
+            worker_scope: self.worker_scope,
         }
     }
 
@@ -91,20 +135,105 @@ impl<PanicHookProvider, TestScopeFactory> DefaultRunner<PanicHookProvider, TestS
     pub fn with_test_scope_factory<WithTestScopeFactory>(
         self,
         test_scope_factory: WithTestScopeFactory,
-    ) -> DefaultRunner<PanicHookProvider, WithTestScopeFactory> {
+    ) -> DefaultRunner<PanicHookProvider, WithTestScopeFactory, WorkerScope> {
         DefaultRunner {
             threads: self.threads,
+            thread_options: self.thread_options,
             panic_hook_provider: self.panic_hook_provider,
             test_scope_factory: Arc::new(test_scope_factory),
+            worker_scope: self.worker_scope,
+        }
+    }
+
+    /// Replace the [`WorkerScope`] used by this runner.
+    ///
+    /// This allows injecting per worker lifecycle hooks without replacing the entire runner.
+    /// Each worker thread calls [`on_worker_start`](WorkerScope::on_worker_start) before its
+    /// first test and [`on_worker_stop`](WorkerScope::on_worker_stop) after its last test.
+    /// The worker state is used to create an additional [`TestScope`] for every test executed on
+    /// that worker.
+    ///
+    /// This replaces the previous worker scope.
+    pub fn with_worker_scope<WithWorkerScope>(
+        self,
+        worker_scope: WithWorkerScope,
+    ) -> DefaultRunner<PanicHookProvider, TestScopeFactory, WithWorkerScope> {
+        DefaultRunner {
+            threads: self.threads,
+            thread_options: self.thread_options,
+            panic_hook_provider: self.panic_hook_provider,
+            test_scope_factory: self.test_scope_factory,
+            worker_scope: Arc::new(worker_scope),
         }
     }
 }
 
-struct DefaultRunnerIterator<'t, 's, I, F, T, Extra>
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ThreadOptions {
+    stack_size: Option<usize>,
+    name: String,
+    fresh_thread_per_test: bool,
+}
+
+impl Default for ThreadOptions {
+    fn default() -> Self {
+        Self {
+            stack_size: None,
+            name: String::from("kitest-worker-{idx}"),
+            fresh_thread_per_test: false,
+        }
+    }
+}
+
+impl ThreadOptions {
+    fn builder(&self, idx: usize) -> thread::Builder {
+        let builder = thread::Builder::new().name(self.name.replace("{idx}", &idx.to_string()));
+        match self.stack_size {
+            Some(size) => builder.stack_size(size),
+            None => builder,
+        }
+    }
+}
+
+fn run_test<'t, F, T, S, Extra>(
+    f: &F,
+    meta: &'t TestMeta<Extra>,
+    test_scope_factory: &T,
+    worker_state: &S,
+) -> TestOutcome
+where
+    F: Fn() -> TestStatus,
+    T: TestScopeFactory<'t, Extra>,
+    S: TestScopeFactory<'t, Extra>,
+{
+    let mut worker_test_scope = worker_state.make_scope();
+    let mut test_scope = test_scope_factory.make_scope();
+    worker_test_scope.before_test(meta);
+    test_scope.before_test(meta);
+
+    let now = Instant::now();
+    let status = f();
+    let duration = now.elapsed();
+    let output = TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::take);
+    let attachments = attachments(&status);
+    let outcome = TestOutcome {
+        status,
+        duration,
+        output,
+        attachments,
+    };
+
+    test_scope.after_test(meta, &outcome);
+    worker_test_scope.after_test(meta, &outcome);
+    outcome
+}
+
+struct DefaultRunnerIterator<'t, 's, I, F, T, W, Extra>
 where
     I: Iterator<Item = (F, &'t TestMeta<Extra>)>,
     F: (Fn() -> TestStatus) + Send,
     T: TestScopeFactory<'t, Extra>,
+    W: WorkerScope<'t, Extra>,
     Extra: 't,
 {
     source: I,
@@ -114,21 +243,25 @@ where
     _workers: Vec<ScopedJoinHandle<'s, ()>>,
     _panic_hook: CapturePanicHookGuard,
     _test_scope_factory: Arc<T>,
+    _worker_scope: Arc<W>,
 }
 
-impl<'t, 's, I, F, T, Extra: Sync> DefaultRunnerIterator<'t, 's, I, F, T, Extra>
+impl<'t, 's, I, F, T, W, Extra: Sync> DefaultRunnerIterator<'t, 's, I, F, T, W, Extra>
 where
     I: Iterator<Item = (F, &'t TestMeta<Extra>)>,
     F: (Fn() -> TestStatus) + Send + 's,
     T: TestScopeFactory<'t, Extra> + Send + Sync + 'static,
+    W: WorkerScope<'t, Extra> + Send + Sync + 'static,
     Extra: 't,
 {
     fn new(
         worker_count: NonZeroUsize,
+        thread_options: &ThreadOptions,
         mut iter: I,
         scope: &'s Scope<'s, 't>,
         panic_hook: PanicHook,
         test_scope_factory: Arc<T>,
+        worker_scope: Arc<W>,
     ) -> Self {
         let (itx, irx) = crossbeam_channel::bounded(worker_count.into());
         let (otx, orx) = crossbeam_channel::bounded(1);
@@ -137,34 +270,57 @@ where
                 let irx = irx.clone();
                 let otx = otx.clone();
                 let test_scope_factory = test_scope_factory.clone();
+                let worker_scope = worker_scope.clone();
                 itx.send(iter.next()).expect("open space in channel");
-                thread::Builder::new()
-                    .name(format!("kitest-worker-{idx}"))
+                let thread_options = thread_options.clone();
+                thread_options
+                    .builder(idx)
                     .spawn_scoped(scope, move || {
+                        let worker_state = (!thread_options.fresh_thread_per_test)
+                            .then(|| worker_scope.on_worker_start(idx));
                         while let Ok(Some((f, meta))) = irx.recv() {
-                            let mut test_scope = test_scope_factory.make_scope();
-                            test_scope.before_test(meta);
-
-                            let now = Instant::now();
-                            let status = f();
-                            let duration = now.elapsed();
-                            let output = TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::take);
-                            let outcome = TestOutcome {
-                                status,
-                                duration,
-                                output,
-                                attachments: TestOutcomeAttachments::default(),
+                            let outcome = match worker_state {
+                                Some(ref worker_state) => {
+                                    run_test(&f, meta, &*test_scope_factory, worker_state)
+                                }
+                                None => {
+                                    let test_scope_factory = &*test_scope_factory;
+                                    let worker_scope = &*worker_scope;
+                                    let joined = thread::scope(|test_thread| {
+                                        thread_options
+                                            .builder(idx)
+                                            .spawn_scoped(test_thread, move || {
+                                                let worker_state =
+                                                    worker_scope.on_worker_start(idx);
+                                                let outcome = run_test(
+                                                    &f,
+                                                    meta,
+                                                    test_scope_factory,
+                                                    &worker_state,
+                                                );
+                                                worker_scope.on_worker_stop(worker_state);
+                                                outcome
+                                            })
+                                            .expect("failed to spawn test thread")
+                                            .join()
+                                    });
+                                    // Forward panics escaping the test thread, as a long
+                                    // lived worker would have died from them too.
+                                    joined.unwrap_or_else(|payload| panic::resume_unwind(payload))
+                                }
                             };
 
-                            test_scope.after_test(meta, &outcome);
                             let send_outcome_res = otx.send((meta, outcome));
                             if send_outcome_res.is_err() {
                                 // If receiver dropped, the work is irrelevant anymore, drop silently.
-                                return;
+                                break;
                             }
                         }
+                        if let Some(worker_state) = worker_state {
+                            worker_scope.on_worker_stop(worker_state);
+                        }
                     })
-                    .expect("name has no null byte")
+                    .expect("failed to spawn worker thread")
             })
             .collect();
 
@@ -176,15 +332,17 @@ where
             _workers: workers,
             _panic_hook: CapturePanicHookGuard::install(panic_hook),
             _test_scope_factory: test_scope_factory,
+            _worker_scope: worker_scope,
         }
     }
 }
 
-impl<'t, 's, I, F, T, Extra> Iterator for DefaultRunnerIterator<'t, 's, I, F, T, Extra>
+impl<'t, 's, I, F, T, W, Extra> Iterator for DefaultRunnerIterator<'t, 's, I, F, T, W, Extra>
 where
     I: Iterator<Item = (F, &'t TestMeta<Extra>)>,
     F: (Fn() -> TestStatus) + Send + 's,
     T: TestScopeFactory<'t, Extra>,
+    W: WorkerScope<'t, Extra>,
     Extra: 't,
 {
     type Item = (&'t TestMeta<Extra>, TestOutcome);
@@ -203,9 +361,10 @@ where
     }
 }
 
-impl<'t, P, T, Extra> TestRunner<'t, Extra> for DefaultRunner<P, T>
+impl<'t, P, T, W, Extra> TestRunner<'t, Extra> for DefaultRunner<P, T, W>
 where
     T: TestScopeFactory<'t, Extra> + Send + Sync + 'static,
+    W: WorkerScope<'t, Extra> + Send + Sync + 'static,
     P: PanicHookProvider,
     Extra: Sync,
 {
@@ -220,13 +379,15 @@ where
         Extra: 't,
     {
         let worker_count =
-            <DefaultRunner<_, _> as TestRunner<Extra>>::worker_count(self, tests.len());
+            <DefaultRunner<_, _, _> as TestRunner<Extra>>::worker_count(self, tests.len());
         DefaultRunnerIterator::new(
             worker_count,
+            &self.thread_options,
             tests,
             scope,
             self.panic_hook_provider.provide(),
             self.test_scope_factory.clone(),
+            self.worker_scope.clone(),
         )
     }
 
@@ -237,10 +398,15 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::{thread, time::Duration};
+    use std::{
+        cell::Cell,
+        sync::atomic::{AtomicUsize, Ordering},
+        thread,
+        time::Duration,
+    };
 
     use super::*;
-    use crate::test_support::*;
+    use crate::{panic::DefaultPanicHandler, test_support::*};
 
     #[test]
     #[cfg_attr(all(ci, target_os = "macos"), ignore = "too slow on macos")]
@@ -308,4 +474,129 @@ mod tests {
             .run();
         assert!(max.duration < Duration::from_millis(20) + PADDING);
     }
+
+    static STARTED: AtomicUsize = AtomicUsize::new(0);
+    static STOPPED: AtomicUsize = AtomicUsize::new(0);
+    static TESTS: AtomicUsize = AtomicUsize::new(0);
+
+    struct CountingWorkerScope;
+
+    struct WorkerState {
+        thread: String,
+        tests: Cell<usize>,
+    }
+
+    impl<'t> TestScopeFactory<'t, ()> for WorkerState {
+        type Scope<'f>
+            = &'f WorkerState
+        where
+            't: 'f,
+            Self: 'f;
+
+        fn make_scope<'f>(&'f self) -> Self::Scope<'f>
+        where
+            't: 'f,
+        {
+            self
+        }
+    }
+
+    impl<'t> TestScope<'t, ()> for &WorkerState {
+        fn before_test(&mut self, _: &'t TestMeta<()>) {
+            assert_eq!(thread::current().name(), Some(self.thread.as_str()));
+            self.tests.set(self.tests.get() + 1);
+        }
+    }
+
+    impl<'t> WorkerScope<'t, ()> for CountingWorkerScope {
+        type State = WorkerState;
+
+        fn on_worker_start(&self, worker: usize) -> Self::State {
+            STARTED.fetch_add(1, Ordering::Relaxed);
+            WorkerState {
+                thread: format!("kitest-worker-{worker}"),
+                tests: Cell::new(0),
+            }
+        }
+
+        fn on_worker_stop(&self, state: Self::State) {
+            STOPPED.fetch_add(1, Ordering::Relaxed);
+            TESTS.fetch_add(state.tests.get(), Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn worker_scope_runs_once_per_worker() {
+        let tests: Vec<_> = (0..8).map(|_| test! {}).collect();
+
+        let report = harness(&tests)
+            .with_runner(
+                DefaultRunner::default()
+                    .with_thread_count(nonzero!(2))
+                    .with_worker_scope(CountingWorkerScope),
+            )
+            .run();
+
+        assert_eq!(report.outcomes.len(), 8);
+        assert_eq!(STARTED.load(Ordering::Relaxed), 2);
+        assert_eq!(STOPPED.load(Ordering::Relaxed), 2);
+        assert_eq!(TESTS.load(Ordering::Relaxed), 8);
+    }
+
+    #[test]
+    fn stack_size_and_thread_name_are_applied() {
+        let tests = &[test! {
+            func: || {
+                assert_eq!(thread::current().name(), Some("parser-0"));
+                // Would overflow the default stack size of 2 MiB.
+                std::hint::black_box([0u8; 4 * 1024 * 1024]);
+            }
+        }];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(
+                DefaultRunner::default()
+                    .with_thread_count(nonzero!(1))
+                    .with_stack_size(16 * 1024 * 1024)
+                    .with_thread_name("parser-{idx}"),
+            )
+            .run();
+
+        assert!(report.outcomes[0].1.passed());
+    }
+
+    #[test]
+    fn fresh_thread_per_test_resets_thread_locals() {
+        thread_local! {
+            static EXECUTED: Cell<bool> = const { Cell::new(false) };
+        }
+
+        let tests: Vec<_> = (0..6)
+            .map(|_| {
+                test! {
+                    func: || {
+                        assert!(!EXECUTED.replace(true));
+                        assert!(thread::current().name().unwrap().starts_with("kitest-worker-"));
+                    }
+                }
+            })
+            .collect();
+
+        let fresh = harness(&tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(
+                DefaultRunner::default()
+                    .with_thread_count(nonzero!(2))
+                    .with_fresh_thread_per_test(true),
+            )
+            .run();
+        assert!(fresh.outcomes.iter().all(|(_, outcome)| outcome.passed()));
+
+        let reused = harness(&tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(DefaultRunner::default().with_thread_count(nonzero!(2)))
+            .run();
+        assert!(reused.outcomes.iter().any(|(_, outcome)| outcome.failed()));
+    }
 }
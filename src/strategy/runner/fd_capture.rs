@@ -0,0 +1,115 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    num::NonZeroUsize,
+    ptr,
+    sync::{Arc, Mutex},
+    thread::Scope,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    capture::{TEST_OUTPUT_CAPTURE, fd},
+    outcome::{TestOutcome, TestStatus},
+    runner::TestRunner,
+    test::TestMeta,
+};
+
+/// A [`TestRunner`] wrapper that captures the real process stdout and stderr of every test.
+///
+/// Each test function is executed inside [`fd::capture`], the captured output is appended to
+/// the output of the test after it finished.
+/// Output written through Kitest's capture macros therefore comes before output written to the
+/// file descriptors.
+///
+/// File descriptors are shared by the whole process, so tests executed by this runner never
+/// overlap, even if the wrapped runner uses multiple threads.
+/// This makes it a natural fit for [`SimpleRunner`](crate::runner::SimpleRunner) or a
+/// [`DefaultRunner`](crate::runner::DefaultRunner) with a single thread.
+///
+/// The reported duration of a test only covers the time the test held the redirection, not the
+/// time it waited for another test to release it.
+///
+/// Formatters writing to [`std::io::stdout`] while a test runs would have their output captured
+/// as well, give them [`fd::original_stdout`] as target.
+///
+/// This runner is only available on Linux.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FdCaptureRunner<Runner> {
+    runner: Runner,
+}
+
+impl<Runner> FdCaptureRunner<Runner> {
+    /// Wrap `runner` to additionally capture the process stdout and stderr.
+    pub fn new(runner: Runner) -> Self {
+        Self { runner }
+    }
+
+    /// Return the wrapped runner.
+    pub fn into_inner(self) -> Runner {
+        self.runner
+    }
+}
+
+impl<'t, R, Extra> TestRunner<'t, Extra> for FdCaptureRunner<R>
+where
+    R: TestRunner<'t, Extra>,
+{
+    fn run<'s, I, F>(
+        &self,
+        tests: I,
+        scope: &'s Scope<'s, 't>,
+    ) -> impl Iterator<Item = (&'t TestMeta<Extra>, TestOutcome)>
+    where
+        I: ExactSizeIterator<Item = (F, &'t TestMeta<Extra>)>,
+        F: (Fn() -> TestStatus) + Send + 's,
+        Extra: 't,
+    {
+        // durations measured once the redirection is held, by test address
+        let durations = Arc::new(Mutex::new(HashMap::new()));
+        let tests_durations = Arc::clone(&durations);
+        let tests = tests.map(move |(f, meta)| {
+            let durations = Arc::clone(&tests_durations);
+            let key = ptr::from_ref(meta).addr();
+            let timed = move || {
+                let now = Instant::now();
+                let status = f();
+                (status, now.elapsed())
+            };
+            let f = move || match fd::capture(&timed) {
+                Ok(((status, duration), output)) => {
+                    durations
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .insert(key, duration);
+                    TEST_OUTPUT_CAPTURE.with_borrow_mut(|capture| capture.append(&output));
+                    status
+                }
+                Err(err) => {
+                    TEST_OUTPUT_CAPTURE.with_borrow_mut(|capture| {
+                        writeln!(capture.stderr(), "failed to capture output: {err}")
+                            .expect("infallible for Vec<u8>")
+                    });
+                    timed().0
+                }
+            };
+            (f, meta)
+        });
+        self.runner
+            .run(tests, scope)
+            .map(move |(meta, mut outcome)| {
+                let duration: Option<Duration> = durations
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .remove(&ptr::from_ref(meta).addr());
+                if let Some(duration) = duration {
+                    outcome.duration = duration;
+                }
+                (meta, outcome)
+            })
+    }
+
+    fn worker_count(&self, tests_count: usize) -> NonZeroUsize {
+        self.runner.worker_count(tests_count)
+    }
+}
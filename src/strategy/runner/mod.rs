@@ -17,8 +17,9 @@
 use std::{num::NonZeroUsize, thread::Scope};
 
 use crate::{
-    outcome::{TestOutcome, TestStatus},
-    test::TestMeta,
+    capture::TEST_PANIC_INFO,
+    outcome::{TestFailure, TestOutcome, TestOutcomeAttachments, TestStatus},
+    test::{TEST_SKIPPED, TestMeta},
 };
 
 mod default;
@@ -30,6 +31,11 @@ pub use simple::*;
 mod smart;
 pub use smart::*;
 
+#[cfg(target_os = "linux")]
+mod fd_capture;
+#[cfg(target_os = "linux")]
+pub use fd_capture::*;
+
 pub mod scope;
 
 /// A strategy for running tests and producing [`TestOutcome`] values.
@@ -72,3 +78,24 @@ pub trait TestRunner<'t, Extra> {
     /// This is used to inform formatters (for example for progress output).
     fn worker_count(&self, tests_count: usize) -> NonZeroUsize;
 }
+
+/// Take the [`PanicInfo`](crate::capture::PanicInfo) and [`Skipped`](crate::test::Skipped)
+/// recorded while the test ran and attach them if they match the status.
+///
+/// The panic info is attached if the test failed by panicking, the skip if the test is ignored.
+/// A test may panic or skip and catch that itself, so both are cleared either way.
+fn attachments(status: &TestStatus) -> TestOutcomeAttachments {
+    let mut attachments = TestOutcomeAttachments::default();
+    let panic_info = TEST_PANIC_INFO.take();
+    if let (
+        Some(panic_info),
+        TestStatus::Failed(TestFailure::Panicked { .. } | TestFailure::PanicMismatch { .. }),
+    ) = (panic_info, status)
+    {
+        attachments.insert(panic_info);
+    }
+    if let (Some(skipped), TestStatus::Ignored { .. }) = (TEST_SKIPPED.take(), status) {
+        attachments.insert(skipped);
+    }
+    attachments
+}
@@ -3,6 +3,9 @@
 //! This module defines a small abstraction for running lifecycle hooks before
 //! and after each test, without having to replace the entire runner
 //! implementation.
+//!
+//! Additionally, [`WorkerScope`] allows running hooks once per worker thread, for setup that is
+//! too expensive to repeat for every test.
 
 use crate::{outcome::TestOutcome, test::TestMeta};
 
@@ -76,3 +79,51 @@ impl<'t, Extra> TestScopeFactory<'t, Extra> for NoScopeFactory {
         NoScope
     }
 }
+
+/// Per worker lifecycle hooks for runners.
+///
+/// Some setup is too expensive to do before every test, but still has to happen on the thread
+/// that executes the tests, for example creating a per thread async runtime, opening a database
+/// connection, or installing a thread local tracing subscriber.
+/// Worker scoping allows adding such hooks to runners like
+/// [`DefaultRunner`](super::DefaultRunner) and [`SimpleRunner`](super::SimpleRunner).
+///
+/// [`on_worker_start`](Self::on_worker_start) is called once on each worker thread before it
+/// executes its first test and produces the worker state.
+/// [`on_worker_stop`](Self::on_worker_stop) is called on the same thread after the worker
+/// executed its last test and receives the state back.
+///
+/// The worker state is itself a [`TestScopeFactory`].
+/// For every test executed on that worker, the runner creates a [`TestScope`] from the state, so
+/// per test hooks can borrow whatever the worker prepared.
+/// Since [`make_scope`](TestScopeFactory::make_scope) only receives a shared reference, state that
+/// needs to be mutated per test should use interior mutability like [`RefCell`](std::cell::RefCell).
+/// The state never leaves its worker thread, so it does not need to be [`Send`].
+pub trait WorkerScope<'t, Extra> {
+    /// The state prepared for a single worker.
+    type State: TestScopeFactory<'t, Extra>;
+
+    /// Called on the worker thread before it executes any test.
+    ///
+    /// `worker` is the index of the worker, starting at `0`.
+    fn on_worker_start(&self, worker: usize) -> Self::State;
+
+    /// Called on the worker thread after it executed its last test.
+    fn on_worker_stop(&self, state: Self::State) {
+        let _ = state;
+    }
+}
+
+/// A [`WorkerScope`] that performs no work.
+///
+/// Its worker state is a [`NoScopeFactory`], so no per test hooks are executed either.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NoWorkerScope;
+
+impl<'t, Extra> WorkerScope<'t, Extra> for NoWorkerScope {
+    type State = NoScopeFactory;
+
+    fn on_worker_start(&self, _: usize) -> Self::State {
+        NoScopeFactory
+    }
+}
@@ -1,14 +1,14 @@
-use std::{num::NonZeroUsize, thread::Scope, time::Instant};
+use std::{marker::PhantomData, num::NonZeroUsize, thread::Scope, time::Instant};
 
 use crate::{
     capture::{
         CapturePanicHookGuard, DefaultPanicHookProvider, OutputCapture, PanicHookProvider,
         TEST_OUTPUT_CAPTURE,
     },
-    outcome::{TestOutcome, TestOutcomeAttachments, TestStatus},
+    outcome::{TestOutcome, TestStatus},
     runner::{
-        TestRunner,
-        scope::{NoScopeFactory, TestScope, TestScopeFactory},
+        TestRunner, attachments,
+        scope::{NoScopeFactory, NoWorkerScope, TestScope, TestScopeFactory, WorkerScope},
     },
     test::TestMeta,
 };
@@ -22,9 +22,10 @@ use crate::{
 /// This is handy in tests and other situations where deterministic ordering is
 /// useful, while still keeping the same behavior around timing and output capture.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SimpleRunner<PanicHookProvider, TestScopeFactory> {
+pub struct SimpleRunner<PanicHookProvider, TestScopeFactory, WorkerScope = NoWorkerScope> {
     panic_hook_provider: PanicHookProvider,
     test_scope_factory: TestScopeFactory,
+    worker_scope: WorkerScope,
 }
 
 impl Default for SimpleRunner<DefaultPanicHookProvider, NoScopeFactory> {
@@ -32,11 +33,14 @@ impl Default for SimpleRunner<DefaultPanicHookProvider, NoScopeFactory> {
         Self {
             panic_hook_provider: DefaultPanicHookProvider,
             test_scope_factory: NoScopeFactory,
+            worker_scope: NoWorkerScope,
         }
     }
 }
 
-impl<PanicHookProvider, TestScopeFactory> SimpleRunner<PanicHookProvider, TestScopeFactory> {
+impl<PanicHookProvider, TestScopeFactory, WorkerScope>
+    SimpleRunner<PanicHookProvider, TestScopeFactory, WorkerScope>
+{
     /// Create a simple runner using the default panic hook provider.
     ///
     /// This is the same as `SimpleRunner::default()`.
@@ -48,10 +52,11 @@ impl<PanicHookProvider, TestScopeFactory> SimpleRunner<PanicHookProvider, TestSc
     pub fn with_panic_hook_provider<WithPanicHookProvider>(
         self,
         panic_hook_provider: WithPanicHookProvider,
-    ) -> SimpleRunner<WithPanicHookProvider, TestScopeFactory> {
+    ) -> SimpleRunner<WithPanicHookProvider, TestScopeFactory, WorkerScope> {
         SimpleRunner {
             panic_hook_provider,
             test_scope_factory: self.test_scope_factory,
+            worker_scope: self.worker_scope,
         }
     }
 
@@ -65,18 +70,119 @@ impl<PanicHookProvider, TestScopeFactory> SimpleRunner<PanicHookProvider, TestSc
     pub fn with_test_scope_factory<WithTestScopeFactory>(
         self,
         test_scope_factory: WithTestScopeFactory,
-    ) -> SimpleRunner<PanicHookProvider, WithTestScopeFactory> {
+    ) -> SimpleRunner<PanicHookProvider, WithTestScopeFactory, WorkerScope> {
         SimpleRunner {
             panic_hook_provider: self.panic_hook_provider,
             test_scope_factory,
+            worker_scope: self.worker_scope,
+        }
+    }
+
+    /// Replace the [`WorkerScope`] used by this runner.
+    ///
+    /// This runner only has a single worker, the current thread.
+    /// [`on_worker_start`](WorkerScope::on_worker_start) is called before the first test and
+    /// [`on_worker_stop`](WorkerScope::on_worker_stop) after the last test.
+    /// The worker state is used to create an additional [`TestScope`] for every test.
+    ///
+    /// This replaces the previous worker scope.
+    pub fn with_worker_scope<WithWorkerScope>(
+        self,
+        worker_scope: WithWorkerScope,
+    ) -> SimpleRunner<PanicHookProvider, TestScopeFactory, WithWorkerScope> {
+        SimpleRunner {
+            panic_hook_provider: self.panic_hook_provider,
+            test_scope_factory: self.test_scope_factory,
+            worker_scope,
+        }
+    }
+}
+
+struct SimpleRunnerIterator<'r, 't, I, T, W, Extra>
+where
+    W: WorkerScope<'t, Extra>,
+{
+    tests: I,
+    test_scope_factory: &'r T,
+    worker_scope: &'r W,
+    worker_state: Option<W::State>,
+    stopped: bool,
+    _panic_hook: CapturePanicHookGuard,
+    _extra: PhantomData<&'t Extra>,
+}
+
+impl<'r, 't, I, F, T, W, Extra> Iterator for SimpleRunnerIterator<'r, 't, I, T, W, Extra>
+where
+    I: Iterator<Item = (F, &'t TestMeta<Extra>)>,
+    F: Fn() -> TestStatus,
+    T: TestScopeFactory<'t, Extra>,
+    W: WorkerScope<'t, Extra>,
+{
+    type Item = (&'t TestMeta<Extra>, TestOutcome);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.stopped {
+            return None;
         }
+
+        let Some((test, meta)) = self.tests.next() else {
+            self.stop_worker();
+            return None;
+        };
+
+        let worker_state = self
+            .worker_state
+            .get_or_insert_with(|| self.worker_scope.on_worker_start(0));
+        let mut worker_test_scope = worker_state.make_scope();
+        let mut test_scope = self.test_scope_factory.make_scope();
+        worker_test_scope.before_test(meta);
+        test_scope.before_test(meta);
+
+        let now = Instant::now();
+        let status = test();
+        let duration = now.elapsed();
+        let output = TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::take);
+        let attachments = attachments(&status);
+
+        let outcome = TestOutcome {
+            status,
+            duration,
+            output,
+            attachments,
+        };
+
+        test_scope.after_test(meta, &outcome);
+        worker_test_scope.after_test(meta, &outcome);
+        Some((meta, outcome))
     }
 }
 
-impl<'t, P, T, Extra> TestRunner<'t, Extra> for SimpleRunner<P, T>
+impl<'t, I, T, W, Extra> SimpleRunnerIterator<'_, 't, I, T, W, Extra>
+where
+    W: WorkerScope<'t, Extra>,
+{
+    fn stop_worker(&mut self) {
+        self.stopped = true;
+        if let Some(worker_state) = self.worker_state.take() {
+            self.worker_scope.on_worker_stop(worker_state);
+        }
+    }
+}
+
+impl<'t, I, T, W, Extra> Drop for SimpleRunnerIterator<'_, 't, I, T, W, Extra>
+where
+    W: WorkerScope<'t, Extra>,
+{
+    fn drop(&mut self) {
+        self.stop_worker();
+    }
+}
+
+impl<'t, P, T, W, Extra> TestRunner<'t, Extra> for SimpleRunner<P, T, W>
 where
     P: PanicHookProvider,
     T: TestScopeFactory<'t, Extra>,
+    W: WorkerScope<'t, Extra>,
 {
     fn run<'s, I, F>(
         &self,
@@ -88,29 +194,16 @@ where
         F: (Fn() -> TestStatus) + Send + 's,
         Extra: 't,
     {
-        let panic_hook = CapturePanicHookGuard::install(self.panic_hook_provider.provide());
-        tests.map(move |(test, meta)| {
-            // keep a ref in here so that the panic_hook only gets dropped after this iterator is done
-            let _panic_hook = &panic_hook;
-
-            let mut test_scope = self.test_scope_factory.make_scope();
-            test_scope.before_test(meta);
-
-            let now = Instant::now();
-            let status = test();
-            let duration = now.elapsed();
-            let output = TEST_OUTPUT_CAPTURE.with_borrow_mut(OutputCapture::take);
-
-            let outcome = TestOutcome {
-                status,
-                duration,
-                output,
-                attachments: TestOutcomeAttachments::default(),
-            };
-
-            test_scope.after_test(meta, &outcome);
-            (meta, outcome)
-        })
+        SimpleRunnerIterator {
+            tests,
+            test_scope_factory: &self.test_scope_factory,
+            worker_scope: &self.worker_scope,
+            worker_state: None,
+            stopped: false,
+            // keep the guard in the iterator so the panic hook only gets dropped after it is done
+            _panic_hook: CapturePanicHookGuard::install(self.panic_hook_provider.provide()),
+            _extra: PhantomData,
+        }
     }
 
     fn worker_count(&self, _: usize) -> NonZeroUsize {
@@ -120,8 +213,14 @@ where
 
 #[cfg(test)]
 mod tests {
+    use std::cell::{Cell, RefCell};
+
     use super::*;
-    use crate::test_support::*;
+    use crate::{
+        capture::{PanicInfo, TEST_PANIC_INFO},
+        panic::DefaultPanicHandler,
+        test_support::*,
+    };
 
     #[test]
     fn run_all_ok_tests() {
@@ -149,4 +248,98 @@ mod tests {
         assert_eq!(*second, "second");
         assert_eq!(*third, "third");
     }
+
+    #[test]
+    fn worker_scope_wraps_all_tests() {
+        thread_local! {
+            static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+        }
+
+        struct RecordingWorkerScope;
+
+        struct WorkerState(Cell<usize>);
+
+        impl<'t> TestScopeFactory<'t, ()> for WorkerState {
+            type Scope<'f>
+                = &'f WorkerState
+            where
+                't: 'f,
+                Self: 'f;
+
+            fn make_scope<'f>(&'f self) -> Self::Scope<'f>
+            where
+                't: 'f,
+            {
+                self
+            }
+        }
+
+        impl<'t> TestScope<'t, ()> for &WorkerState {
+            fn before_test(&mut self, meta: &'t TestMeta<()>) {
+                self.0.set(self.0.get() + 1);
+                EVENTS.with_borrow_mut(|events| events.push(format!("test {}", meta.name)));
+            }
+        }
+
+        impl<'t> WorkerScope<'t, ()> for RecordingWorkerScope {
+            type State = WorkerState;
+
+            fn on_worker_start(&self, worker: usize) -> Self::State {
+                EVENTS.with_borrow_mut(|events| events.push(format!("start {worker}")));
+                WorkerState(Cell::new(0))
+            }
+
+            fn on_worker_stop(&self, state: Self::State) {
+                EVENTS.with_borrow_mut(|events| events.push(format!("stop {}", state.0.get())));
+            }
+        }
+
+        let tests = &[test! {name: "a"}, test! {name: "b"}];
+        let report = harness(tests)
+            .with_runner(SimpleRunner::default().with_worker_scope(RecordingWorkerScope))
+            .run();
+        assert_eq!(report.outcomes.len(), 2);
+
+        let events = EVENTS.take();
+        assert_eq!(events, ["start 0", "test a", "test b", "stop 2"]);
+    }
+
+    #[test]
+    fn panic_info_is_only_attached_to_panic_failures() {
+        let tests = &[
+            test! {name: "caught", func: || {
+                let _ = std::panic::catch_unwind(|| panic!("caught"));
+            }},
+            test! {name: "panicked", func: || -> () { panic!("oh no") }},
+        ];
+
+        let report = harness(tests)
+            .with_panic_handler(DefaultPanicHandler)
+            .with_runner(SimpleRunner::default())
+            .run();
+        assert!(report.outcomes[0].1.passed());
+        assert!(
+            report.outcomes[0]
+                .1
+                .attachments
+                .get::<PanicInfo>()
+                .is_none()
+        );
+        let info = report.outcomes[1].1.attachments.get::<PanicInfo>().unwrap();
+        assert_eq!(info.payload, "oh no");
+
+        let tests = &[test! {func: || {
+            let _ = std::panic::catch_unwind(|| panic!("caught"));
+        }}];
+        let report = harness(tests).with_runner(SimpleRunner::default()).run();
+        assert!(report.outcomes[0].1.passed());
+        assert!(
+            report.outcomes[0]
+                .1
+                .attachments
+                .get::<PanicInfo>()
+                .is_none()
+        );
+        assert!(TEST_PANIC_INFO.with_borrow(Option::is_none));
+    }
 }
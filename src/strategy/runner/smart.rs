@@ -5,7 +5,7 @@ use crate::{
     outcome::{TestOutcome, TestStatus},
     runner::{
         DefaultRunner, SimpleRunner, TestRunner,
-        scope::{NoScopeFactory, TestScopeFactory},
+        scope::{NoScopeFactory, NoWorkerScope, TestScopeFactory, WorkerScope},
     },
     test::TestMeta,
 };
@@ -22,10 +22,10 @@ use crate::{
 /// The best choice depends on the workload, which is why this is not the default runner
 /// implementation.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct SmartRunner<PanicHookProvider, TestScopeFactory> {
+pub struct SmartRunner<PanicHookProvider, TestScopeFactory, WorkerScope = NoWorkerScope> {
     threshold: usize,
-    simple: SimpleRunner<PanicHookProvider, TestScopeFactory>,
-    default: DefaultRunner<PanicHookProvider, TestScopeFactory>,
+    simple: SimpleRunner<PanicHookProvider, TestScopeFactory, WorkerScope>,
+    default: DefaultRunner<PanicHookProvider, TestScopeFactory, WorkerScope>,
 }
 
 impl Default for SmartRunner<DefaultPanicHookProvider, NoScopeFactory> {
@@ -38,7 +38,9 @@ impl Default for SmartRunner<DefaultPanicHookProvider, NoScopeFactory> {
     }
 }
 
-impl<PanicHookProvider, TestScopeFactory> SmartRunner<PanicHookProvider, TestScopeFactory> {
+impl<PanicHookProvider, TestScopeFactory, WorkerScope>
+    SmartRunner<PanicHookProvider, TestScopeFactory, WorkerScope>
+{
     /// Create a smart runner using the default panic hook provider.
     ///
     /// This is the same as `SmartRunner::default()`.
@@ -66,7 +68,7 @@ impl<PanicHookProvider, TestScopeFactory> SmartRunner<PanicHookProvider, TestSco
     pub fn with_panic_hook_provider<WithPanicHookProvider: Clone>(
         self,
         panic_hook_provider: WithPanicHookProvider,
-    ) -> SmartRunner<WithPanicHookProvider, TestScopeFactory> {
+    ) -> SmartRunner<WithPanicHookProvider, TestScopeFactory, WorkerScope> {
         SmartRunner {
             threshold: self.threshold,
             simple: self
@@ -87,7 +89,7 @@ impl<PanicHookProvider, TestScopeFactory> SmartRunner<PanicHookProvider, TestSco
     pub fn with_test_scope_factory<WithTestScopeFactory: Clone>(
         self,
         test_scope_factory: WithTestScopeFactory,
-    ) -> SmartRunner<PanicHookProvider, WithTestScopeFactory> {
+    ) -> SmartRunner<PanicHookProvider, WithTestScopeFactory, WorkerScope> {
         SmartRunner {
             threshold: self.threshold,
             simple: self
@@ -96,6 +98,23 @@ impl<PanicHookProvider, TestScopeFactory> SmartRunner<PanicHookProvider, TestSco
             default: self.default.with_test_scope_factory(test_scope_factory),
         }
     }
+
+    /// Replace the [`WorkerScope`](super::scope::WorkerScope) used by this runner.
+    ///
+    /// This allows injecting per worker lifecycle hooks without replacing the entire runner.
+    /// The worker scope is applied to both the internal [`SimpleRunner`] and [`DefaultRunner`].
+    ///
+    /// This replaces the previous worker scope.
+    pub fn with_worker_scope<WithWorkerScope: Clone>(
+        self,
+        worker_scope: WithWorkerScope,
+    ) -> SmartRunner<PanicHookProvider, TestScopeFactory, WithWorkerScope> {
+        SmartRunner {
+            threshold: self.threshold,
+            simple: self.simple.with_worker_scope(worker_scope.clone()),
+            default: self.default.with_worker_scope(worker_scope),
+        }
+    }
 }
 
 enum SmartRunnerIterator<IS, ID> {
@@ -119,10 +138,11 @@ where
     }
 }
 
-impl<'t, P, T, Extra> TestRunner<'t, Extra> for SmartRunner<P, T>
+impl<'t, P, T, W, Extra> TestRunner<'t, Extra> for SmartRunner<P, T, W>
 where
     P: PanicHookProvider,
     T: TestScopeFactory<'t, Extra> + Send + Sync + 'static,
+    W: WorkerScope<'t, Extra> + Send + Sync + 'static,
     Extra: Sync,
 {
     fn run<'s, I, F>(
@@ -136,27 +156,26 @@ where
         Extra: 't,
     {
         match tests.len() <= self.threshold {
-            true => SmartRunnerIterator::Simple(<SimpleRunner<_, _> as TestRunner<Extra>>::run(
+            true => SmartRunnerIterator::Simple(<SimpleRunner<_, _, _> as TestRunner<Extra>>::run(
                 &self.simple,
                 tests,
                 scope,
             )),
-            false => SmartRunnerIterator::Default(<DefaultRunner<_, _> as TestRunner<Extra>>::run(
-                &self.default,
-                tests,
-                scope,
-            )),
+            false => SmartRunnerIterator::Default(
+                <DefaultRunner<_, _, _> as TestRunner<Extra>>::run(&self.default, tests, scope),
+            ),
         }
     }
 
     fn worker_count(&self, test_count: usize) -> NonZeroUsize {
         match test_count <= self.threshold {
             true => {
-                <SimpleRunner<_, _> as TestRunner<Extra>>::worker_count(&self.simple, test_count)
-            }
-            false => {
-                <DefaultRunner<_, _> as TestRunner<Extra>>::worker_count(&self.default, test_count)
+                <SimpleRunner<_, _, _> as TestRunner<Extra>>::worker_count(&self.simple, test_count)
             }
+            false => <DefaultRunner<_, _, _> as TestRunner<Extra>>::worker_count(
+                &self.default,
+                test_count,
+            ),
         }
     }
 }
@@ -7,12 +7,25 @@
 //! [`Test`] values.
 
 use std::{
+    any::Any,
     borrow::Cow,
+    cell::RefCell,
+    error::Error,
     fmt::{Debug, Display},
+    future,
     ops::Deref,
+    pin::{Pin, pin},
+    process::ExitCode,
+    task::{Context, Poll},
 };
 
-use crate::{Whatever, ignore::IgnoreStatus, panic::PanicExpectation};
+use crate::{
+    Whatever,
+    executor::{DefaultExecutor, Executor},
+    ignore::IgnoreStatus,
+    outcome::TestStatus,
+    panic::PanicExpectation,
+};
 
 /// A single test case.
 ///
@@ -56,10 +69,33 @@ impl<Extra> Test<Extra> {
     }
 
     pub(crate) fn call(&self) -> TestResult {
-        self.function.call()
+        crate::check::collect(|| self.function.call())
     }
 }
 
+/// Marks the end of short backtraces, see [`short_backtrace`](crate::capture::short_backtrace).
+///
+/// Like the function of the same name in the standard library, this must not be inlined and must
+/// not be a tail call, otherwise it would not show up in backtraces.
+/// It directly calls the test function, so no frame of Kitest ends up between the two.
+#[inline(never)]
+fn __rust_begin_short_backtrace<T>(f: impl FnOnce() -> T) -> T {
+    let result = f();
+    std::hint::black_box(());
+    result
+}
+
+/// Like [`__rust_begin_short_backtrace`], but polls the future of an async test function.
+#[inline(never)]
+fn __rust_begin_short_backtrace_poll<Fut: Future>(
+    future: Pin<&mut Fut>,
+    cx: &mut Context<'_>,
+) -> Poll<Fut::Output> {
+    let result = future.poll(cx);
+    std::hint::black_box(());
+    result
+}
+
 impl<Extra> Deref for Test<Extra> {
     type Target = TestMeta<Extra>;
 
@@ -281,6 +317,48 @@ impl TestFnHandle {
         Self::Owned(Box::new(f))
     }
 
+    /// Construct a [`TestFnHandle`] from an async test function.
+    ///
+    /// This creates an [`Owned`](TestFnHandle::Owned) variant that calls `f` and drives the
+    /// returned future to completion using the [`DefaultExecutor`].
+    /// The future runs on the thread executing the test, so panics inside the future are handled
+    /// by the [`TestPanicHandler`](super::strategy::panic::TestPanicHandler) like for any other
+    /// test.
+    ///
+    /// The output of the future is converted into a [`TestResult`], just like the return value of
+    /// a synchronous test function.
+    ///
+    /// Use [`from_future_fn_with`](Self::from_future_fn_with) if the future needs a specific
+    /// runtime.
+    pub fn from_future_fn<F, Fut>(f: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future,
+        Fut::Output: Into<TestResult>,
+    {
+        Self::from_future_fn_with(DefaultExecutor, f)
+    }
+
+    /// Construct a [`TestFnHandle`] from an async test function and a custom [`Executor`].
+    ///
+    /// This works like [`from_future_fn`](Self::from_future_fn), but the returned future is
+    /// driven by `executor` instead of the [`DefaultExecutor`].
+    /// This allows running tests on any runtime that can block on a future.
+    pub fn from_future_fn_with<E, F, Fut>(executor: E, f: F) -> Self
+    where
+        E: Executor + Send + Sync + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future,
+        Fut::Output: Into<TestResult>,
+    {
+        Self::Owned(Box::new(move || {
+            let mut future = pin!(__rust_begin_short_backtrace(&f));
+            executor.block_on(future::poll_fn(|cx| {
+                __rust_begin_short_backtrace_poll(future.as_mut(), cx)
+            }))
+        }))
+    }
+
     /// Construct a [`TestFnHandle`] from a static test function object.
     ///
     /// This creates a [`Static`](TestFnHandle::Static) variant from a reference with `'static`
@@ -299,7 +377,7 @@ impl TestFnHandle {
     /// It provides a uniform way for the harness and panic handler to execute tests.
     pub fn call(&self) -> TestResult {
         match self {
-            Self::Ptr(f) => f(),
+            Self::Ptr(f) => __rust_begin_short_backtrace(*f),
             Self::Owned(f) => f.call_test(),
             Self::Static(f) => f.call_test(),
         }
@@ -319,11 +397,18 @@ impl TestFnHandle {
 /// convertible into a [`TestResult`].
 /// This includes:
 ///
-/// - `()`
-/// - `Result<T, E>` where `E: Debug`
+/// - `()` and other types implementing [`TestDetails`]
+/// - `Result<T, E>` where `T: TestDetails` and `E: Debug + 'static`
+/// - `Option<()>`, `bool` and [`ExitCode`], failing on `None`, `false` and failing exit codes
+///
+/// Async test functions are supported through [`TestFnHandle::from_future_fn`], which drives the
+/// returned future to completion inside [`call_test`](Self::call_test).
 ///
 /// This makes normal test functions work naturally, while still allowing custom closures or
 /// adapters to be stored as `dyn TestFn`.
+///
+/// The blanket implementation calls the function as the last frame of
+/// [short backtraces](crate::capture::short_backtrace), custom implementations are not trimmed.
 pub trait TestFn {
     /// Call the test function and produce a [`TestResult`].
     fn call_test(&self) -> TestResult;
@@ -335,7 +420,7 @@ where
     T: Into<TestResult>,
 {
     fn call_test(&self) -> TestResult {
-        (self)().into()
+        __rust_begin_short_backtrace(self).into()
     }
 }
 
@@ -362,9 +447,15 @@ where
 /// `TestResult` is designed to be ergonomic:
 ///
 /// - It can be created from `()`, which maps to `Ok(None)`.
-/// - It can be created from `Result<(), E>` where `E: Error`. On failure,
+/// - It can be created from any [`TestDetails`], which maps to `Ok(details)`.
+/// - It can be created from `Result<T, E>` where `E: Debug + 'static`. On failure,
 ///   the error is converted into a `Whatever`, preserving its formatted
 ///   representation while fitting into the unified result type.
+///   Boxed errors, like `Box<dyn Error>`, keep their [`source`](Error::source) chain.
+///   A `Box<dyn Error + Send + Sync>` is kept as is, so the original error can be recovered via
+///   [`Whatever::as_error`] and downcasting.
+///   Returning that type lets `?` box any error that is [`Send`] and [`Sync`].
+///   A returned [`Whatever`] is passed through unchanged.
 ///
 /// While typically returned from regular Rust test functions, a runner may
 /// also construct a `TestResult` directly, for example when validating
@@ -372,17 +463,298 @@ where
 #[derive(Debug, Clone, PartialEq)]
 pub struct TestResult(pub Result<Option<Whatever>, Whatever>);
 
-impl From<()> for TestResult {
-    fn from(_: ()) -> Self {
-        Self(Ok(None))
+impl<T: TestDetails> From<T> for TestResult {
+    fn from(value: T) -> Self {
+        Self(Ok(value.into_details()))
+    }
+}
+
+impl<T: TestDetails, E: Debug + 'static> From<Result<T, E>> for TestResult {
+    fn from(v: Result<T, E>) -> Self {
+        TestResult(v.map(T::into_details).map_err(error_to_whatever))
+    }
+}
+
+/// Convert the error returned by a test into a [`Whatever`].
+///
+/// Boxed errors keep their source chain, `Box<dyn Error + Send + Sync>` additionally stays
+/// available for downcasting.
+/// Other errors are stored as their [`Debug`] representation.
+fn error_to_whatever<E: Debug + 'static>(error: E) -> Whatever {
+    let mut error = Some(error);
+    let any: &mut dyn Any = &mut error;
+    if let Some(error) = any.downcast_mut::<Option<Whatever>>() {
+        return error.take().expect("error is set");
+    }
+    if let Some(error) = any.downcast_mut::<Option<Box<dyn Error + Send + Sync>>>() {
+        return Whatever::from_error(error.take().expect("error is set"));
+    }
+    if let Some(Some(error)) = any.downcast_ref::<Option<Box<dyn Error + Send>>>() {
+        return Whatever::from_error(ErrorSnapshot::new(&**error));
+    }
+    if let Some(Some(error)) = any.downcast_ref::<Option<Box<dyn Error>>>() {
+        return Whatever::from_error(ErrorSnapshot::new(&**error));
+    }
+    Whatever::from(format!("{:?}", error.expect("error is set")))
+}
+
+/// A copy of an error that is not [`Sync`], keeping its messages and source chain.
+struct ErrorSnapshot {
+    debug: String,
+    display: String,
+    source: Option<Box<ErrorSnapshot>>,
+}
+
+impl ErrorSnapshot {
+    fn new(error: &dyn Error) -> Self {
+        Self {
+            debug: format!("{error:?}"),
+            display: error.to_string(),
+            source: error.source().map(|source| Box::new(Self::new(source))),
+        }
+    }
+}
+
+impl Debug for ErrorSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.debug)
+    }
+}
+
+impl Display for ErrorSnapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.display)
+    }
+}
+
+impl Error for ErrorSnapshot {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|source| source as &dyn Error)
+    }
+}
+
+impl From<Option<()>> for TestResult {
+    fn from(v: Option<()>) -> Self {
+        match v {
+            Some(()) => Self(Ok(None)),
+            None => Self(Err(Whatever::from(String::from("test returned `None`")))),
+        }
+    }
+}
+
+impl From<bool> for TestResult {
+    fn from(v: bool) -> Self {
+        match v {
+            true => Self(Ok(None)),
+            false => Self(Err(Whatever::from(String::from("test returned `false`")))),
+        }
+    }
+}
+
+impl From<ExitCode> for TestResult {
+    fn from(code: ExitCode) -> Self {
+        match code == ExitCode::SUCCESS {
+            true => Self(Ok(None)),
+            false => Self(Err(Whatever::from(format!(
+                "test returned a failing exit code: {code:?}"
+            )))),
+        }
+    }
+}
+
+/// A value returned by a passing test.
+///
+/// Tests may return any type implementing this trait, directly or as the `Ok` value of a
+/// [`Result`].
+/// The value is converted into the details of the [`TestResult`], which end up in
+/// [`TestStatus::Other`].
+/// Returning `None` from [`into_details`](Self::into_details) reports a plain pass instead.
+///
+/// Implement this for your own return types to report them:
+///
+/// ```
+/// use kitest::{Whatever, test::TestDetails};
+///
+/// struct Measurement(u64);
+///
+/// impl TestDetails for Measurement {
+///     fn into_details(self) -> Option<Whatever> {
+///         Some(Whatever::from(format!("took {} ms", self.0)))
+///     }
+/// }
+/// ```
+///
+/// Kitest implements this for `()`, [`Whatever`], strings and primitive values.
+pub trait TestDetails {
+    /// Convert the returned value into details, or `None` if there are none.
+    fn into_details(self) -> Option<Whatever>;
+}
+
+impl TestDetails for () {
+    fn into_details(self) -> Option<Whatever> {
+        None
+    }
+}
+
+impl TestDetails for Whatever {
+    fn into_details(self) -> Option<Whatever> {
+        Some(self)
+    }
+}
+
+macro_rules! impl_test_details {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl TestDetails for $ty {
+                fn into_details(self) -> Option<Whatever> {
+                    Some(Whatever::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_test_details!(
+    String,
+    &'static str,
+    Cow<'static, str>,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+);
+
+impl TestResult {
+    /// Create a result for a test that decided at runtime that it cannot run.
+    ///
+    /// The test is reported as [`TestStatus::Ignored`] with the given reason.
+    /// Return this from a test function to skip it, or use [`skip!`](crate::skip) to skip from
+    /// anywhere inside the test.
+    pub fn skipped(reason: impl Into<Cow<'static, str>>) -> Self {
+        Self(Ok(Some(Whatever::from(Skipped {
+            reason: Some(reason.into()),
+        }))))
+    }
+}
+
+/// A test skipped itself at runtime.
+///
+/// Tests skip themselves either by returning [`TestResult::skipped`] or by calling
+/// [`skip!`](crate::skip), which unwinds with this value as panic payload.
+/// Both end up as [`TestStatus::Ignored`], with the [`Skipped`] attached to the outcome, see
+/// [`TestOutcome::skipped`](crate::outcome::TestOutcome::skipped).
+///
+/// Unwinding skips are only recognized by panic handlers catching panics, like the
+/// [`DefaultPanicHandler`](crate::panic::DefaultPanicHandler).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    /// Why the test was skipped.
+    pub reason: Option<Cow<'static, str>>,
+}
+
+impl Skipped {
+    /// End the current test by unwinding with `self` as payload.
+    ///
+    /// This uses [`resume_unwind`](std::panic::resume_unwind), so the panic hook is not invoked
+    /// and nothing is printed.
+    pub fn unwind(self) -> ! {
+        std::panic::resume_unwind(Box::new(self))
+    }
+
+    /// Turn the skip into the status of the test.
+    ///
+    /// This records the skip in [`TEST_SKIPPED`] for the runner to attach to the outcome.
+    /// Panic handlers recognizing skips use this.
+    pub fn report(self) -> TestStatus {
+        let reason = self.reason.clone();
+        TEST_SKIPPED.set(Some(self));
+        TestStatus::Ignored { reason }
     }
 }
 
-impl<E: Debug> From<Result<(), E>> for TestResult {
-    fn from(v: Result<(), E>) -> Self {
-        TestResult(
-            v.map(|_| None)
-                .map_err(|e| Whatever::from(format!("{e:?}"))),
-        )
+thread_local! {
+    /// The [`Skipped`] of the test running on this thread, recorded by [`Skipped::report`].
+    ///
+    /// Runners take it after each test and attach it to the outcome of ignored tests.
+    pub static TEST_SKIPPED: RefCell<Option<Skipped>> = const { RefCell::new(None) };
+}
+
+impl Display for Skipped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            Some(reason) => write!(f, "skipped, {reason}"),
+            None => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Skip the running test.
+///
+/// This ends the test immediately and reports it as
+/// [`TestStatus::Ignored`], even from deep inside helper functions.
+/// The arguments are formatted like [`format!`] into the reason.
+///
+/// ```
+/// # fn test() {
+/// if std::env::var_os("DOCKER_HOST").is_none() {
+///     kitest::skip!("no docker available");
+/// }
+/// # }
+/// ```
+///
+/// The skip unwinds the stack, see [`Skipped::unwind`].
+/// It is only recognized by panic handlers catching panics, like the
+/// [`DefaultPanicHandler`](crate::panic::DefaultPanicHandler).
+#[macro_export]
+macro_rules! skip {
+    () => {
+        $crate::test::Skipped { reason: ::std::option::Option::None }.unwind()
+    };
+    ($($arg:tt)+) => {
+        $crate::test::Skipped {
+            reason: ::std::option::Option::Some(::std::borrow::Cow::Owned(::std::format!($($arg)+))),
+        }
+        .unwind()
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outcome::TestFailure;
+
+    fn status(result: impl Into<TestResult>) -> TestStatus {
+        result.into().into()
+    }
+
+    #[test]
+    fn return_types_map_to_status() {
+        assert_eq!(status(()), TestStatus::Passed);
+        assert_eq!(status(Some(())), TestStatus::Passed);
+        assert_eq!(status(true), TestStatus::Passed);
+        assert_eq!(status(ExitCode::SUCCESS), TestStatus::Passed);
+        assert_eq!(
+            status(Ok::<_, String>(42)),
+            TestStatus::Other(Whatever::from(42))
+        );
+        assert_eq!(
+            status("details"),
+            TestStatus::Other(Whatever::from("details"))
+        );
+
+        for failing in [status(None::<()>), status(false), status(ExitCode::FAILURE)] {
+            assert!(matches!(failing, TestStatus::Failed(TestFailure::Error(_))));
+        }
     }
 }
@@ -1,10 +1,14 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    io,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     TestHarness,
     capture::DefaultPanicHookProvider,
     filter::NoFilter,
-    formatter::no::NoFormatter,
+    formatter::{common::color::SupportsColor, no::NoFormatter},
     ignore::{IgnoreStatus, NoIgnore},
     panic::{NoPanicHandler, PanicExpectation},
     runner::{SimpleRunner, scope::NoScopeFactory},
@@ -90,6 +94,9 @@ pub fn harness(
         panic_handler: NoPanicHandler,
         runner: SimpleRunner::default(),
         formatter: NoFormatter,
+        no_capture: false,
+        output_limit: None,
+        explain: false,
     }
 }
 
@@ -104,3 +111,29 @@ macro_rules! nonzero {
 }
 
 pub(crate) use nonzero;
+
+/// A formatter target that keeps the output in memory.
+#[derive(Debug, Default, Clone)]
+pub struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Buffer {
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl io::Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SupportsColor for Buffer {
+    fn supports_color(&self) -> bool {
+        false
+    }
+}
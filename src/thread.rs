@@ -0,0 +1,245 @@
+//! Spawning threads that keep capturing output.
+//!
+//! Output capture is thread local, see [`TEST_OUTPUT_CAPTURE`](crate::capture::TEST_OUTPUT_CAPTURE).
+//! Threads spawned with [`std::thread`] therefore write their output, and panic messages, into a
+//! capture no test ever looks at.
+//!
+//! This module mirrors the spawning functions of [`std::thread`].
+//! Threads spawned through it share the output capture of the spawning thread via a
+//! [`CaptureHandle`], so their output ends up in the output of the test that spawned them.
+//! They also share its [`CheckHandle`], so their failed [`check!`](crate::check!)s fail that
+//! test.
+//! Name threads with [`Builder::name`] to see which thread panicked in the panic message.
+
+use std::{
+    io,
+    thread::{self, JoinHandle, ScopedJoinHandle},
+};
+
+use crate::{capture::CaptureHandle, check::CheckHandle};
+
+fn captured<F, T>(f: F) -> impl FnOnce() -> T
+where
+    F: FnOnce() -> T,
+{
+    let capture = CaptureHandle::current();
+    let checks = CheckHandle::current();
+    move || {
+        capture.install();
+        if let Some(checks) = checks {
+            checks.install();
+        }
+        f()
+    }
+}
+
+/// Spawn a new thread that captures into the output of the current thread.
+///
+/// This is the capturing version of [`std::thread::spawn`].
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread, use [`Builder::spawn`] to handle that.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    Builder::new().spawn(f).expect("failed to spawn thread")
+}
+
+/// Create a scope for spawning scoped threads that capture into the output of the current thread.
+///
+/// This is the capturing version of [`std::thread::scope`].
+/// Unlike the standard library, the closure receives the [`Scope`] by value.
+/// It is [`Copy`], so it can be moved into spawned threads just like a reference.
+pub fn scope<'env, F, T>(f: F) -> T
+where
+    F: for<'scope> FnOnce(Scope<'scope, 'env>) -> T,
+{
+    thread::scope(|scope| f(Scope { inner: scope }))
+}
+
+/// A scope to spawn scoped threads in, see [`scope`].
+#[derive(Debug, Clone, Copy)]
+pub struct Scope<'scope, 'env: 'scope> {
+    inner: &'scope thread::Scope<'scope, 'env>,
+}
+
+impl<'scope, 'env> Scope<'scope, 'env> {
+    /// Spawn a new scoped thread that captures into the output of the current thread.
+    ///
+    /// This is the capturing version of [`std::thread::Scope::spawn`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the OS fails to create a thread, use [`Builder::spawn_scoped`] to handle that.
+    pub fn spawn<F, T>(self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        Builder::new()
+            .spawn_scoped(self, f)
+            .expect("failed to spawn thread")
+    }
+
+    /// Return the underlying scope of the standard library.
+    ///
+    /// Threads spawned on it do not capture output.
+    pub fn inner(self) -> &'scope thread::Scope<'scope, 'env> {
+        self.inner
+    }
+}
+
+/// Thread factory to configure the properties of a new thread.
+///
+/// This is the capturing version of [`std::thread::Builder`].
+#[derive(Debug)]
+#[must_use = "must eventually spawn the thread"]
+pub struct Builder(thread::Builder);
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Builder {
+    /// Create a builder with the default configuration.
+    pub fn new() -> Self {
+        Self(thread::Builder::new())
+    }
+
+    /// Name the thread.
+    ///
+    /// The name is shown in panic messages.
+    pub fn name(self, name: String) -> Self {
+        Self(self.0.name(name))
+    }
+
+    /// Set the stack size of the thread in bytes.
+    pub fn stack_size(self, size: usize) -> Self {
+        Self(self.0.stack_size(size))
+    }
+
+    /// Spawn a new thread that captures into the output of the current thread.
+    pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        self.0.spawn(captured(f))
+    }
+
+    /// Spawn a new scoped thread that captures into the output of the current thread.
+    pub fn spawn_scoped<'scope, 'env, F, T>(
+        self,
+        scope: Scope<'scope, 'env>,
+        f: F,
+    ) -> io::Result<ScopedJoinHandle<'scope, T>>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        self.0.spawn_scoped(scope.inner, captured(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::{
+        capture::{OutputCapture, TEST_OUTPUT_CAPTURE},
+        outcome::{TestFailure, TestStatus},
+        test_support::*,
+    };
+
+    fn write_stdout(s: &str) {
+        TEST_OUTPUT_CAPTURE
+            .with_borrow_mut(|capture| capture.stdout().write_all(s.as_bytes()))
+            .unwrap();
+    }
+
+    fn stdout(capture: &OutputCapture) -> String {
+        capture.read_stdout().fold(String::new(), |s, chunk| {
+            s + &String::from_utf8_lossy(chunk)
+        })
+    }
+
+    #[test]
+    fn spawned_output_is_captured_in_order() {
+        let tests = &[test! {
+            func: || {
+                write_stdout("a");
+                spawn(|| write_stdout("b")).join().unwrap();
+                scope(|s| {
+                    s.spawn(move || {
+                        write_stdout("c");
+                        s.spawn(|| write_stdout("d")).join().unwrap();
+                    });
+                });
+                write_stdout("e");
+            }
+        }];
+
+        let report = harness(tests).run();
+        assert_eq!(stdout(&report.outcomes[0].1.output), "abcde");
+        assert!(
+            TEST_OUTPUT_CAPTURE
+                .with_borrow_mut(OutputCapture::take)
+                .raw()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn panic_message_names_thread() {
+        let tests = &[test! {
+            func: || {
+                let res = Builder::new()
+                    .name(String::from("helper"))
+                    .spawn(|| panic!("oh no"))
+                    .unwrap()
+                    .join();
+                assert!(res.is_err());
+            }
+        }];
+
+        let report = harness(tests).run();
+        let stderr = report.outcomes[0]
+            .1
+            .output
+            .read_stderr()
+            .fold(String::new(), |s, chunk| {
+                s + &String::from_utf8_lossy(chunk)
+            });
+        assert!(stderr.contains("thread 'helper'"));
+        assert!(stderr.contains("oh no"));
+    }
+
+    #[test]
+    fn spawned_checks_fail_the_test() {
+        let tests = &[test! {
+            func: || {
+                spawn(|| crate::check!(false, "in spawned")).join().unwrap();
+                scope(|s| {
+                    s.spawn(|| crate::check!(false, "in scoped"));
+                });
+            }
+        }];
+
+        let report = harness(tests).run();
+        let TestStatus::Failed(TestFailure::Multiple(failures)) = &report.outcomes[0].1.status
+        else {
+            panic!(
+                "expected failed checks, got {:?}",
+                report.outcomes[0].1.status
+            );
+        };
+        let messages: Vec<_> = failures.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages, ["in spawned", "in scoped"]);
+    }
+}
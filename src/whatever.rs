@@ -1,6 +1,9 @@
 use std::{
     any::Any,
+    error::Error,
     fmt::{self, Debug, Display, Formatter},
+    ptr,
+    sync::Arc,
 };
 
 /// Type erased user data with a fixed set of trait bounds.
@@ -14,6 +17,9 @@ use std::{
 /// - [`PartialEq`]
 /// - [`Send`] and [`Sync`]
 ///
+/// Values that cannot be cloned or compared, like most errors, can be stored via
+/// [`Whatever::shared`] and [`Whatever::from_error`] instead.
+///
 /// The main purpose of `Whatever` is to allow attaching "nice to have" user data in places where
 /// it is useful for reporting or formatting, but not required for executing tests.
 /// In those cases, the runtime indirection is acceptable.
@@ -32,24 +38,146 @@ use std::{
 /// Kitest specific tradeoff to keep the generic surface area under control.
 pub struct Whatever(Box<dyn WhateverImpl>);
 
-trait WhateverImpl: Debug + Display + Any + Send + Sync {
+trait WhateverImpl: Debug + Display + Send + Sync {
     fn clone_whatever(&self) -> Whatever;
     fn eq_whatever(&self, other: &Whatever) -> bool;
+    fn value_ref(&self) -> &dyn Any;
+    fn value_mut(&mut self) -> &mut dyn Any;
+    fn into_value(self: Box<Self>) -> Box<dyn Any + Send + Sync>;
+
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        None
+    }
+}
+
+/// A value stored by [`Whatever::from`].
+struct Value<T>(T);
+
+/// A value stored by [`Whatever::shared`].
+struct Shared<T>(Arc<T>);
+
+/// An error stored by [`Whatever::from_error`].
+struct SharedError(Shared<Box<dyn Error + Send + Sync>>);
+
+impl<T: Debug> Debug for Value<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T: Display> Display for Value<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
 }
 
-impl<T> WhateverImpl for T
+impl<T> WhateverImpl for Value<T>
 where
     T: Debug + Display + Clone + PartialEq + Send + Sync + 'static,
 {
     fn clone_whatever(&self) -> Whatever {
-        Whatever(Box::new(self.clone()))
+        Whatever(Box::new(Value(self.0.clone())))
     }
 
     fn eq_whatever(&self, other: &Whatever) -> bool {
         let Some(other) = other.as_any_ref().downcast_ref() else {
             return false;
         };
-        self.eq(other)
+        self.0.eq(other)
+    }
+
+    fn value_ref(&self) -> &dyn Any {
+        &self.0
+    }
+
+    fn value_mut(&mut self) -> &mut dyn Any {
+        &mut self.0
+    }
+
+    fn into_value(self: Box<Self>) -> Box<dyn Any + Send + Sync> {
+        Box::new(self.0)
+    }
+}
+
+impl<T: Debug> Debug for Shared<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl<T: Display> Display for Shared<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl<T> WhateverImpl for Shared<T>
+where
+    T: Debug + Display + Send + Sync + 'static,
+{
+    fn clone_whatever(&self) -> Whatever {
+        Whatever(Box::new(Shared(Arc::clone(&self.0))))
+    }
+
+    fn eq_whatever(&self, other: &Whatever) -> bool {
+        ptr::addr_eq(self.value_ref(), other.as_any_ref())
+    }
+
+    fn value_ref(&self) -> &dyn Any {
+        &*self.0
+    }
+
+    fn value_mut(&mut self) -> &mut dyn Any {
+        // checked twice, returning the first borrow would extend it over the fallback
+        match Arc::get_mut(&mut self.0).is_some() {
+            true => Arc::get_mut(&mut self.0).expect("checked above"),
+            false => &mut self.0,
+        }
+    }
+
+    fn into_value(self: Box<Self>) -> Box<dyn Any + Send + Sync> {
+        match Arc::try_unwrap(self.0) {
+            Ok(value) => Box::new(value),
+            Err(shared) => Box::new(shared),
+        }
+    }
+}
+
+impl Debug for SharedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for SharedError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl WhateverImpl for SharedError {
+    fn clone_whatever(&self) -> Whatever {
+        Whatever(Box::new(SharedError(Shared(Arc::clone(&self.0.0)))))
+    }
+
+    fn eq_whatever(&self, other: &Whatever) -> bool {
+        self.0.eq_whatever(other)
+    }
+
+    fn value_ref(&self) -> &dyn Any {
+        self.0.value_ref()
+    }
+
+    fn value_mut(&mut self) -> &mut dyn Any {
+        self.0.value_mut()
+    }
+
+    fn into_value(self: Box<Self>) -> Box<dyn Any + Send + Sync> {
+        Box::new(self.0).into_value()
+    }
+
+    fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&**self.0.0)
     }
 }
 
@@ -89,7 +217,35 @@ impl Whatever {
     pub fn from<T: Debug + Display + Clone + PartialEq + Send + Sync + 'static>(
         value: T,
     ) -> Whatever {
-        Self(Box::new(value))
+        Self(Box::new(Value(value)))
+    }
+
+    /// Create a new [`Whatever`] from a value that cannot be cloned or compared.
+    ///
+    /// The value is stored behind an [`Arc`].
+    /// Clones of the returned `Whatever` share the value, and two of them are only equal if they
+    /// share the same value.
+    ///
+    /// While clones exist, the value cannot be accessed mutably or taken out.
+    /// [`as_any_mut`](Self::as_any_mut) and [`into_any`](Self::into_any) then return the
+    /// `Arc<T>` instead of `T`.
+    pub fn shared<T: Debug + Display + Send + Sync + 'static>(value: T) -> Whatever {
+        Self(Box::new(Shared(Arc::new(value))))
+    }
+
+    /// Create a new [`Whatever`] from an error.
+    ///
+    /// The error is boxed like [`shared`](Self::shared) values and stays available as error via
+    /// [`as_error`](Self::as_error), including its [`source`](Error::source) chain.
+    /// The `Any` based accessors return the `Box<dyn Error + Send + Sync>`, which can be
+    /// downcast further into the original error type.
+    pub fn from_error(error: impl Into<Box<dyn Error + Send + Sync>>) -> Whatever {
+        Self(Box::new(SharedError(Shared(Arc::new(error.into())))))
+    }
+
+    /// Return the stored error, if this was created via [`from_error`](Self::from_error).
+    pub fn as_error(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.as_error()
     }
 
     /// Convert this [`Whatever`] into a boxed [`Any`].
@@ -99,20 +255,133 @@ impl Whatever {
     ///
     /// This is useful when ownership of the erased value is needed.
     pub fn into_any(self) -> Box<dyn Any + Send + Sync> {
-        self.0
+        self.0.into_value()
     }
 
     /// Get a shared reference to the underlying value as [`Any`].
     ///
     /// This allows inspecting or downcasting the stored value without taking ownership.
     pub fn as_any_ref(&self) -> &dyn Any {
-        &self.0
+        self.0.value_ref()
     }
 
     /// Get a mutable reference to the underlying value as [`Any`].
     ///
     /// This allows mutating or downcasting the stored value without taking ownership.
     pub fn as_any_mut(&mut self) -> &mut dyn Any {
-        &mut self.0
+        self.0.value_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fmt, io};
+
+    use super::*;
+    use crate::test::TestResult;
+
+    #[derive(Debug)]
+    struct Outer(io::Error);
+
+    impl Display for Outer {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("outer")
+        }
+    }
+
+    impl Error for Outer {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    fn chain(error: &(dyn Error + 'static)) -> Vec<String> {
+        std::iter::successors(Some(error), |&e| e.source())
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn errors_keep_type_and_source_chain() {
+        let outer = || Outer(io::Error::other("inner"));
+
+        let sync: Result<(), Box<dyn Error + Send + Sync>> = Err(outer().into());
+        let TestResult(Err(err)) = sync.into() else {
+            panic!("expected an error");
+        };
+        let error = err.as_error().unwrap();
+        assert!(error.downcast_ref::<Outer>().is_some());
+        assert_eq!(chain(error), ["outer", "inner"]);
+        assert_eq!(err.clone(), err);
+        assert_ne!(err, Whatever::from_error(outer()));
+
+        let unsync: Result<(), Box<dyn Error>> = Err(outer().into());
+        let TestResult(Err(err)) = unsync.into() else {
+            panic!("expected an error");
+        };
+        assert_eq!(chain(err.as_error().unwrap()), ["outer", "inner"]);
+        assert_eq!(format!("{err:?}"), format!("{:?}", outer()));
+
+        let unsync: Result<(), Box<dyn Error + Send>> = Err(Box::new(outer()));
+        let TestResult(Err(err)) = unsync.into() else {
+            panic!("expected an error");
+        };
+        assert_eq!(chain(err.as_error().unwrap()), ["outer", "inner"]);
+
+        let debug: Result<(), &str> = Err("oh no");
+        let TestResult(Err(err)) = debug.into() else {
+            panic!("expected an error");
+        };
+        assert!(err.as_error().is_none());
+        assert_eq!(err.to_string(), "\"oh no\"");
+
+        let whatever = Whatever::from(String::from("details"));
+        let passed: Result<(), Whatever> = Err(whatever.clone());
+        let TestResult(Err(err)) = passed.into() else {
+            panic!("expected an error");
+        };
+        assert_eq!(err, whatever);
+        assert!(err.as_error().is_none());
+    }
+
+    #[test]
+    fn shared_values_are_accessible_while_unique() {
+        #[derive(Debug)]
+        struct Counter(usize);
+
+        impl Display for Counter {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        let mut value = Whatever::shared(Counter(1));
+        value.as_any_mut().downcast_mut::<Counter>().unwrap().0 += 1;
+        assert_eq!(value.to_string(), "2");
+
+        let mut clone = value.clone();
+        assert_eq!(clone, value);
+        assert!(clone.as_any_mut().downcast_mut::<Counter>().is_none());
+        assert!(value.as_any_ref().downcast_ref::<Counter>().is_some());
+
+        drop(clone);
+        assert_eq!(value.into_any().downcast::<Counter>().unwrap().0, 2);
+    }
+
+    #[test]
+    fn any_accessors_return_the_stored_value() {
+        let mut value = Whatever::from(String::from("value"));
+        assert_eq!(
+            value.as_any_ref().downcast_ref::<String>().unwrap(),
+            "value"
+        );
+
+        value
+            .as_any_mut()
+            .downcast_mut::<String>()
+            .unwrap()
+            .push('!');
+        assert_eq!(value, Whatever::from(String::from("value!")));
+        assert!(value.as_any_ref().downcast_ref::<Whatever>().is_none());
     }
 }
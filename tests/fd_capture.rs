@@ -0,0 +1,163 @@
+//! Tests for the file descriptor capture.
+//!
+//! Redirecting file descriptors affects the whole process, so these tests run in their own test
+//! binary without the default test harness.
+//! The checks run one after another on the main thread, nothing else writes to the redirected
+//! descriptors.
+
+#[cfg(target_os = "linux")]
+fn main() {
+    let checks: &[(&str, fn())] = &[
+        (
+            "captures_std_writes_and_subprocesses",
+            linux::captures_std_writes_and_subprocesses,
+        ),
+        (
+            "does_not_block_on_large_output",
+            linux::does_not_block_on_large_output,
+        ),
+        (
+            "appends_fd_output_to_test_output",
+            linux::appends_fd_output_to_test_output,
+        ),
+        (
+            "duration_excludes_waiting_for_redirection",
+            linux::duration_excludes_waiting_for_redirection,
+        ),
+        (
+            "no_capture_forwards_output",
+            linux::no_capture_forwards_output,
+        ),
+    ];
+
+    println!("\nrunning {} tests", checks.len());
+    for (name, check) in checks {
+        check();
+        println!("test {name} ... ok");
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn main() {}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        io::{self, Write},
+        process::Command,
+        sync::mpsc,
+        thread,
+        time::Duration,
+    };
+
+    use kitest::{
+        capture::fd,
+        formatter::no::NoFormatter,
+        prelude::*,
+        runner::{FdCaptureRunner, SimpleRunner},
+    };
+
+    fn collect(chunks: impl Iterator<Item = impl AsRef<[u8]>>) -> String {
+        chunks.fold(String::new(), |s, chunk| {
+            s + &String::from_utf8_lossy(chunk.as_ref())
+        })
+    }
+
+    fn test(f: &'static (dyn TestFn + Send + Sync)) -> Test {
+        Test::new(
+            TestFnHandle::from_static_obj(f),
+            TestMeta {
+                name: Cow::Borrowed("test"),
+                ignore: IgnoreStatus::Run,
+                should_panic: PanicExpectation::ShouldNotPanic,
+                origin: origin!(),
+                extra: (),
+            },
+        )
+    }
+
+    pub fn captures_std_writes_and_subprocesses() {
+        let (value, capture) = fd::capture(|| {
+            println!("from std");
+            eprintln!("from stderr");
+            Command::new("sh")
+                .args(["-c", "echo from child"])
+                .status()
+                .unwrap();
+            42
+        })
+        .unwrap();
+
+        assert_eq!(value, 42);
+        let stdout = collect(capture.read_stdout());
+        assert!(stdout.contains("from std\n"));
+        assert!(stdout.contains("from child\n"));
+        assert!(collect(capture.read_stderr()).contains("from stderr\n"));
+    }
+
+    pub fn does_not_block_on_large_output() {
+        let line = "x".repeat(1023);
+        let (_, capture) = fd::capture(|| {
+            let mut stdout = io::stdout().lock();
+            for _ in 0..256 {
+                writeln!(stdout, "{line}").unwrap();
+            }
+        })
+        .unwrap();
+
+        assert!(capture.raw().len() >= 256 * 1024);
+    }
+
+    pub fn appends_fd_output_to_test_output() {
+        let tests = &[test(&|| {
+            kitest::println!("from kitest");
+            println!("from std");
+        })];
+
+        let report = kitest::harness(tests)
+            .with_runner(FdCaptureRunner::new(SimpleRunner::default()))
+            .with_formatter(NoFormatter)
+            .run();
+
+        let stdout = collect(report.outcomes[0].1.output.read_stdout());
+        let (kitest, std) = stdout.split_once('\n').unwrap();
+        assert_eq!(kitest, "from kitest");
+        assert!(std.contains("from std\n"));
+    }
+
+    pub fn duration_excludes_waiting_for_redirection() {
+        let (locked_tx, locked_rx) = mpsc::channel();
+        let holder = thread::spawn(move || {
+            fd::capture(|| {
+                locked_tx.send(()).unwrap();
+                thread::sleep(Duration::from_millis(300));
+            })
+            .unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        let tests = &[test(&|| ())];
+        let report = kitest::harness(tests)
+            .with_runner(FdCaptureRunner::new(SimpleRunner::default()))
+            .with_formatter(NoFormatter)
+            .run();
+        holder.join().unwrap();
+
+        assert!(report.outcomes[0].1.duration < Duration::from_millis(100));
+    }
+    pub fn no_capture_forwards_output() {
+        let tests = &[test(&|| kitest::println!("live output"))];
+
+        let (report, capture) = fd::capture(|| {
+            kitest::harness(tests)
+                .with_no_capture(true)
+                .with_formatter(NoFormatter)
+                .run()
+        })
+        .unwrap();
+
+        assert!(report.outcomes[0].1.passed());
+        assert!(report.outcomes[0].1.output.raw().is_empty());
+        assert!(collect(capture.read_stdout()).contains("live output\n"));
+    }
+}